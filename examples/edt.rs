@@ -1,8 +1,12 @@
 // mod save_img;
 
 use clap::Parser;
-use edt::{edt, edt_fmm, edt_fmm_cb, FMMCallbackData};
-use image::{GenericImageView, ImageBuffer, Luma, Rgb};
+use edt::{
+    edt, edt_fmm, edt_fmm_cb,
+    image_support::{edt_image, ImageEdtOptions},
+    DistanceField, FMMCallbackData,
+};
+use image::{GenericImageView, ImageBuffer, Rgb};
 use std::time::Instant;
 
 #[derive(Parser, Debug)]
@@ -29,27 +33,38 @@ fn main() -> std::io::Result<()> {
 
     let img = image::open(args.file_name).unwrap();
     let dims = img.dimensions();
+    let shape = (dims.0 as usize, dims.1 as usize);
     println!("dimensions {:?}, color: {:?}", dims, img.color());
 
-    let img2 = img.into_luma8();
-    let flat_samples = img2.as_flat_samples();
-
-    let slice = flat_samples.image_slice().unwrap();
-
-    println!("len {}", slice.len());
+    // Black pixels are the foreground, and the others are obstacles.
+    let opts = ImageEdtOptions {
+        invert: true,
+        ..ImageEdtOptions::default()
+    };
+    let compute_edt = |fast_marching| -> Vec<f64> {
+        edt_image(
+            &img,
+            ImageEdtOptions {
+                fast_marching,
+                ..opts
+            },
+        )
+        .into_raw()
+        .into_iter()
+        .map(f64::from)
+        .collect()
+    };
 
     let start = Instant::now();
 
     let mut i = 0;
 
     let edt_f64 = if args.diff {
-        let fmm = edt_fmm(&slice, (dims.0 as usize, dims.1 as usize), true);
-        let exact = edt(&slice, (dims.0 as usize, dims.1 as usize), true);
-        let result: Vec<_> = fmm
-            .into_iter()
-            .zip(exact.into_iter())
-            .map(|(a, b)| a - b)
-            .collect();
+        // edt_image returns f32, so use f64 versions to keep the precision of the difference.
+        let luma = img.to_luma8();
+        let fmm = edt_fmm(luma.as_raw(), shape, true);
+        let exact = edt(luma.as_raw(), shape, true);
+        let result: Vec<_> = fmm.into_iter().zip(exact).map(|(a, b)| a - b).collect();
         println!(
             "Max diff: {}",
             result
@@ -63,8 +78,8 @@ fn main() -> std::io::Result<()> {
     } else if args.fast_marching {
         if let Some(progress_steps) = args.progress_steps {
             edt_fmm_cb(
-                &slice,
-                (dims.0 as usize, dims.1 as usize),
+                img.to_luma8().as_raw(),
+                shape,
                 true,
                 |FMMCallbackData {
                      map: edt_f64,
@@ -72,12 +87,7 @@ fn main() -> std::io::Result<()> {
                      ..
                  }| {
                     if i % progress_steps == 0 {
-                        let max_value =
-                            edt_f64.iter().map(|p| *p).reduce(f64::max).unwrap().max(1.);
-                        let edt_u8: Vec<_> = edt_f64
-                            .iter()
-                            .map(|p| (*p / max_value * 255.) as u8)
-                            .collect();
+                        let edt_u8 = DistanceField::new(edt_f64.to_vec(), shape).to_u8();
 
                         let mut edt_img: ImageBuffer<Rgb<u8>, Vec<_>> =
                             ImageBuffer::new(dims.0, dims.1);
@@ -98,26 +108,27 @@ fn main() -> std::io::Result<()> {
                 },
             )
         } else {
-            edt_fmm(&slice, (dims.0 as usize, dims.1 as usize), true)
+            compute_edt(true)
         }
     } else {
-        edt(&slice, (dims.0 as usize, dims.1 as usize), true)
+        compute_edt(false)
     };
 
     let duration = start.elapsed().as_micros();
     println!("time: {:?}ms", duration as f64 / 1e3);
-    let max_value = edt_f64.iter().map(|p| p.abs()).reduce(f64::max).unwrap();
+    let edt_field = DistanceField::new(edt_f64, shape);
     if args.diff {
-        let edt_img = edt_f64
+        let max_value = edt_field.max().unwrap().max(-edt_field.min().unwrap());
+        let edt_img = edt_field
+            .data()
             .iter()
-            .map(|p| {
+            .flat_map(|p| {
                 [
                     ((-p).max(0.) / max_value * 255.) as u8,
                     0,
                     (p.max(0.) / max_value * 255.) as u8,
                 ]
             })
-            .flatten()
             .collect();
 
         let edt_img: ImageBuffer<Rgb<u8>, Vec<u8>> =
@@ -125,15 +136,7 @@ fn main() -> std::io::Result<()> {
 
         edt_img.save("edt.png").unwrap();
     } else {
-        let edt_img = edt_f64
-            .iter()
-            .map(|p| (*p / max_value * 255.) as u8)
-            .collect();
-
-        let edt_img: ImageBuffer<Luma<u8>, Vec<u8>> =
-            ImageBuffer::from_vec(dims.0, dims.1, edt_img).unwrap();
-
-        edt_img.save("edt.png").unwrap();
+        edt_field.to_luma8().save("edt.png").unwrap();
     };
 
     Ok(())
@@ -0,0 +1,167 @@
+use super::{exact_edt::edt_sq_by, BoolLike};
+
+/// Search space of [`chamfer_match`] in addition to translation.
+#[derive(Clone, Debug)]
+pub struct ChamferOptions {
+    /// Rotation angles of the template in radians to try.
+    pub rotations: Vec<f64>,
+    /// Scale factors of the template to try.
+    pub scales: Vec<f64>,
+}
+
+impl Default for ChamferOptions {
+    /// Translation only.
+    fn default() -> Self {
+        Self {
+            rotations: vec![0.],
+            scales: vec![1.],
+        }
+    }
+}
+
+/// A placement of the template found by [`chamfer_match`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ChamferPlacement {
+    /// The pixel where the origin of the template is placed.
+    pub offset: (usize, usize),
+    /// The rotation angle in radians.
+    pub rotation: f64,
+    /// The scale factor.
+    pub scale: f64,
+    /// The mean distance from the template points to the nearest edge. Lower is better.
+    pub score: f64,
+}
+
+/// The result of [`chamfer_match`].
+pub struct ChamferMatch {
+    /// The best placement, or `None` if the template fits nowhere.
+    pub best: Option<ChamferPlacement>,
+    /// The best score over rotations and scales for each offset, or infinity where the
+    /// template does not fit in the image.
+    pub score_map: Vec<f64>,
+}
+
+/// Match a template of edge points against an edge map with chamfer matching.
+///
+/// Nonzero pixels in `edges` are edges. The score of a placement is the mean of the EDT of the
+/// edges under the transformed template points, which are given relative to the template origin.
+/// Placements where any point falls outside of the image are not considered.
+pub fn chamfer_match<T: BoolLike>(
+    edges: &[T],
+    shape: (usize, usize),
+    template: &[(f64, f64)],
+    options: &ChamferOptions,
+) -> ChamferMatch {
+    let dist: Vec<f64> = edt_sq_by(shape, |(x, y)| !edges[x + y * shape.0].as_bool(), false)
+        .into_iter()
+        .map(f64::sqrt)
+        .collect();
+
+    let mut score_map = vec![f64::INFINITY; shape.0 * shape.1];
+    let mut best: Option<ChamferPlacement> = None;
+
+    for &rotation in &options.rotations {
+        let (sin, cos) = rotation.sin_cos();
+        for &scale in &options.scales {
+            let points: Vec<(isize, isize)> = template
+                .iter()
+                .map(|(x, y)| {
+                    (
+                        (scale * (x * cos - y * sin)).round() as isize,
+                        (scale * (x * sin + y * cos)).round() as isize,
+                    )
+                })
+                .collect();
+
+            for oy in 0..shape.1 {
+                for ox in 0..shape.0 {
+                    let score = match placement_score(&dist, shape, &points, (ox, oy)) {
+                        Some(score) => score,
+                        None => continue,
+                    };
+                    let cell = &mut score_map[ox + oy * shape.0];
+                    *cell = cell.min(score);
+                    if best.map(|best| score < best.score).unwrap_or(true) {
+                        best = Some(ChamferPlacement {
+                            offset: (ox, oy),
+                            rotation,
+                            scale,
+                            score,
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    ChamferMatch {
+        best: best.filter(|best| best.score.is_finite()),
+        score_map,
+    }
+}
+
+fn placement_score(
+    dist: &[f64],
+    shape: (usize, usize),
+    points: &[(isize, isize)],
+    offset: (usize, usize),
+) -> Option<f64> {
+    let mut sum = 0.;
+    for (dx, dy) in points {
+        let x = offset.0 as isize + dx;
+        let y = offset.1 as isize + dy;
+        if x < 0 || shape.0 as isize <= x || y < 0 || shape.1 as isize <= y {
+            return None;
+        }
+        sum += dist[x as usize + y as usize * shape.0];
+    }
+    Some(sum / points.len() as f64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const L_SHAPE: [(f64, f64); 5] = [(0., 0.), (0., 1.), (0., 2.), (1., 2.), (2., 2.)];
+
+    fn edge_map(shape: (usize, usize), points: &[(isize, isize)]) -> Vec<bool> {
+        let mut edges = vec![false; shape.0 * shape.1];
+        for (x, y) in points {
+            edges[*x as usize + *y as usize * shape.0] = true;
+        }
+        edges
+    }
+
+    #[test]
+    fn test_translation() {
+        let shape = (10, 8);
+        let edges = edge_map(shape, &[(3, 2), (3, 3), (3, 4), (4, 4), (5, 4), (8, 1)]);
+        let res = chamfer_match(&edges, shape, &L_SHAPE, &ChamferOptions::default());
+        let best = res.best.unwrap();
+        assert_eq!(best.offset, (3, 2));
+        assert_eq!(best.score, 0.);
+        assert_eq!(res.score_map[3 + 2 * shape.0], 0.);
+        assert!(0. < res.score_map[4 + 2 * shape.0]);
+        // The template does not fit at the bottom right corner.
+        assert_eq!(res.score_map[9 + 7 * shape.0], f64::INFINITY);
+    }
+
+    #[test]
+    fn test_rotation() {
+        let shape = (10, 8);
+        // The L shape rotated by 90 degrees around the origin at (6, 3)
+        let edges = edge_map(shape, &[(6, 3), (5, 3), (4, 3), (4, 4), (4, 5)]);
+        let options = ChamferOptions {
+            rotations: (0..4)
+                .map(|i| i as f64 * std::f64::consts::FRAC_PI_2)
+                .collect(),
+            scales: vec![1.],
+        };
+        let best = chamfer_match(&edges, shape, &L_SHAPE, &options)
+            .best
+            .unwrap();
+        assert_eq!(best.offset, (6, 3));
+        assert_eq!(best.rotation, std::f64::consts::FRAC_PI_2);
+        assert_eq!(best.score, 0.);
+    }
+}
@@ -0,0 +1,220 @@
+use super::GridPos;
+use std::collections::HashMap;
+
+/// A polyline of an iso-contour returned by [`contours`].
+#[derive(Clone, Debug, PartialEq)]
+pub struct Contour {
+    /// Vertices of the polyline in pixel coordinates with sub-pixel precision.
+    pub points: Vec<(f64, f64)>,
+    /// Whether the polyline forms a loop. The first vertex is not repeated at the end.
+    /// Contours that reach the border of the image are open.
+    pub closed: bool,
+}
+
+/// Identifies an edge between two adjacent pixels: the pixel at the top or left end and
+/// whether the edge is horizontal.
+type EdgeId = (GridPos, bool);
+
+/// Extract iso-contours of a scalar field, such as the output of [`crate::edt`], at the given level
+/// with marching squares.
+///
+/// Vertices are linearly interpolated along the edges between pixel centers, and segments are
+/// joined into polylines. Saddle cells are disambiguated by the average of the four corners.
+/// Pixels whose value is greater than or equal to the level are regarded as inside.
+///
+/// Infinite values, such as the sentinel of [`crate::edt_bounded`], are allowed. Since they are
+/// infinitely steep, the vertex on an edge between finite and infinite pixels is put on the
+/// finite one.
+pub fn contours(field: &[f64], shape: (usize, usize), level: f64) -> Vec<Contour> {
+    let inside = |(x, y): GridPos| level <= field[x + y * shape.0];
+
+    let mut points = HashMap::<EdgeId, (f64, f64)>::new();
+    let mut segments = vec![];
+
+    for y in 0..shape.1.saturating_sub(1) {
+        for x in 0..shape.0.saturating_sub(1) {
+            let corners = [(x, y), (x + 1, y), (x + 1, y + 1), (x, y + 1)];
+            let states = corners.map(inside);
+            // Edge i connects corner i and corner (i + 1) % 4.
+            let edges: [EdgeId; 4] = [
+                ((x, y), true),
+                ((x + 1, y), false),
+                ((x, y + 1), true),
+                ((x, y), false),
+            ];
+            let crossing: Vec<usize> = (0..4)
+                .filter(|i| states[*i] != states[(i + 1) % 4])
+                .collect();
+
+            for i in &crossing {
+                points.entry(edges[*i]).or_insert_with(|| {
+                    let (a, b) = (corners[*i], corners[(i + 1) % 4]);
+                    let (va, vb) = (field[a.0 + a.1 * shape.0], field[b.0 + b.1 * shape.0]);
+                    let t = if va.is_infinite() {
+                        1.
+                    } else if vb.is_infinite() {
+                        0.
+                    } else {
+                        (level - va) / (vb - va)
+                    };
+                    (
+                        a.0 as f64 + t * (b.0 as f64 - a.0 as f64),
+                        a.1 as f64 + t * (b.1 as f64 - a.1 as f64),
+                    )
+                });
+            }
+
+            match crossing.len() {
+                2 => segments.push((edges[crossing[0]], edges[crossing[1]])),
+                4 => {
+                    let center = corners
+                        .iter()
+                        .map(|(x, y)| field[x + y * shape.0])
+                        .sum::<f64>()
+                        / 4.;
+                    // Cut off the corners that are not connected through the center.
+                    let cut_state = level > center;
+                    for i in (0..4).filter(|i| states[*i] == cut_state) {
+                        segments.push((edges[(i + 3) % 4], edges[i]));
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    let mut adjacency = HashMap::<EdgeId, Vec<usize>>::new();
+    for (i, (a, b)) in segments.iter().enumerate() {
+        adjacency.entry(*a).or_default().push(i);
+        adjacency.entry(*b).or_default().push(i);
+    }
+
+    let mut used = vec![false; segments.len()];
+    let trace = |used: &mut [bool], start_seg: usize, start: EdgeId| {
+        let mut chain = vec![points[&start]];
+        let (mut seg, mut edge) = (start_seg, start);
+        loop {
+            used[seg] = true;
+            let (a, b) = segments[seg];
+            edge = if a == edge { b } else { a };
+            match adjacency[&edge].iter().find(|s| !used[**s]) {
+                Some(next) => {
+                    chain.push(points[&edge]);
+                    seg = *next;
+                }
+                None => {
+                    if edge != start {
+                        chain.push(points[&edge]);
+                    }
+                    return chain;
+                }
+            }
+        }
+    };
+
+    let mut ret = vec![];
+
+    // Open contours start and end at the border, where an edge has only one segment.
+    let mut ends: Vec<_> = adjacency
+        .iter()
+        .filter(|(_, segs)| segs.len() == 1)
+        .map(|(edge, segs)| (*edge, segs[0]))
+        .collect();
+    ends.sort_by_key(|(((x, y), horz), _)| (*y, *x, *horz));
+    for (edge, seg) in ends {
+        if !used[seg] {
+            ret.push(Contour {
+                points: trace(&mut used, seg, edge),
+                closed: false,
+            });
+        }
+    }
+
+    for seg in 0..segments.len() {
+        if !used[seg] {
+            ret.push(Contour {
+                points: trace(&mut used, seg, segments[seg].0),
+                closed: true,
+            });
+        }
+    }
+
+    ret
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_closed() {
+        let shape = (20, 20);
+        let field: Vec<f64> = (0..shape.0 * shape.1)
+            .map(|i| {
+                let (x, y) = ((i % shape.0) as f64, (i / shape.0) as f64);
+                ((x - 9.5).powf(2.) + (y - 10.).powf(2.)).sqrt()
+            })
+            .collect();
+        let res = contours(&field, shape, 5.);
+        assert_eq!(res.len(), 1);
+        assert!(res[0].closed);
+        assert!(20 < res[0].points.len());
+        for (x, y) in &res[0].points {
+            let r = ((x - 9.5).powf(2.) + (y - 10.).powf(2.)).sqrt();
+            assert!((r - 5.).abs() < 0.1, "r: {}", r);
+        }
+    }
+
+    #[test]
+    fn test_open() {
+        let shape = (5, 4);
+        let field: Vec<f64> = (0..shape.0 * shape.1)
+            .map(|i| (i % shape.0) as f64)
+            .collect();
+        let res = contours(&field, shape, 2.5);
+        assert_eq!(
+            res,
+            vec![Contour {
+                points: vec![(2.5, 0.), (2.5, 1.), (2.5, 2.), (2.5, 3.)],
+                closed: false,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_infinite() {
+        let res = contours(&[f64::INFINITY, 1., f64::INFINITY, 1.], (2, 2), 2.);
+        assert_eq!(res.len(), 1);
+        assert_eq!(res[0].points.len(), 2);
+        assert!(res[0].points.contains(&(1., 0.)));
+        assert!(res[0].points.contains(&(1., 1.)));
+
+        // Pixels farther than the bound are infinite in a bounded EDT.
+        let shape = (20, 20);
+        let mut map = vec![true; shape.0 * shape.1];
+        map[10 + 10 * shape.0] = false;
+        let field = crate::edt_bounded(&map, shape, false, 4.);
+        for level in [2., 4.5] {
+            let res = contours(&field, shape, level);
+            assert!(!res.is_empty());
+            for (x, y) in res.iter().flat_map(|c| &c.points) {
+                assert!(x.is_finite() && y.is_finite(), "({}, {})", x, y);
+            }
+        }
+    }
+
+    #[test]
+    fn test_saddle() {
+        let shape = (2, 2);
+        // The center is inside, so the two inside corners are connected.
+        let res = contours(&[1., 0., 0., 1.], shape, 0.4);
+        assert_eq!(res.len(), 2);
+        for contour in &res {
+            assert_eq!(contour.points.len(), 2);
+        }
+        // The outside top right corner is cut off.
+        assert!(res
+            .iter()
+            .any(|c| c.points.contains(&(0.6, 0.)) && c.points.contains(&(1., 0.4))));
+    }
+}
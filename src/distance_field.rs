@@ -0,0 +1,350 @@
+use super::{edt, edt_fmm, BoolLike, GridPos};
+use std::ops::{Index, IndexMut};
+
+/// Interpolation method for [`DistanceField::sample`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    /// Bilinear interpolation of the 2x2 neighboring pixels.
+    Bilinear,
+    /// Bicubic (Catmull-Rom) interpolation of the 4x4 neighboring pixels.
+    Bicubic,
+}
+
+/// Behavior of [`DistanceField::sample`] outside of the image.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum OutOfBounds {
+    /// Use the value at the nearest point on the border, so the gradient across it is zero.
+    Clamp,
+    /// Return a constant value and zero gradient.
+    Constant(f64),
+}
+
+/// A 2D container of a distance field, which can be sampled at continuous coordinates.
+///
+/// It wraps the `Vec<f64>` and the shape returned by [`crate::edt`] or [`crate::edt_fmm`],
+/// or you can get one directly from [`edt_field`] or [`edt_fmm_field`].
+/// Pixels can be accessed by `field[(x, y)]` or [`DistanceField::rows`].
+/// Pixel `(x, y)` is at coordinates `(x as f64, y as f64)`, so the image spans
+/// `[0, width - 1] x [0, height - 1]`.
+#[derive(Clone, Debug)]
+pub struct DistanceField {
+    data: Vec<f64>,
+    shape: (usize, usize),
+    interpolation: Interpolation,
+    out_of_bounds: OutOfBounds,
+}
+
+impl DistanceField {
+    /// Wrap a field with bilinear interpolation and clamping at the border.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `data` does not match the shape.
+    pub fn new(data: Vec<f64>, shape: (usize, usize)) -> Self {
+        assert_eq!(
+            data.len(),
+            shape.0 * shape.1,
+            "DistanceField data length does not match the shape"
+        );
+        Self {
+            data,
+            shape,
+            interpolation: Interpolation::Bilinear,
+            out_of_bounds: OutOfBounds::Clamp,
+        }
+    }
+
+    /// Set the interpolation method.
+    pub fn with_interpolation(mut self, interpolation: Interpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Set the behavior outside of the image.
+    pub fn with_out_of_bounds(mut self, out_of_bounds: OutOfBounds) -> Self {
+        self.out_of_bounds = out_of_bounds;
+        self
+    }
+
+    /// The dimensions of the field in pixels.
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    /// The width of the field in pixels.
+    pub fn width(&self) -> usize {
+        self.shape.0
+    }
+
+    /// The height of the field in pixels.
+    pub fn height(&self) -> usize {
+        self.shape.1
+    }
+
+    /// The field values in row-major order.
+    pub fn data(&self) -> &[f64] {
+        &self.data
+    }
+
+    /// Mutable field values in row-major order.
+    pub fn data_mut(&mut self) -> &mut [f64] {
+        &mut self.data
+    }
+
+    /// A row of the field.
+    pub fn row(&self, y: usize) -> &[f64] {
+        &self.data[y * self.shape.0..(y + 1) * self.shape.0]
+    }
+
+    /// Iterate rows of the field from the top.
+    pub fn rows(&self) -> impl Iterator<Item = &[f64]> {
+        (0..self.shape.1).map(move |y| self.row(y))
+    }
+
+    /// The minimum value, or `None` if the field is empty.
+    pub fn min(&self) -> Option<f64> {
+        self.data.iter().copied().reduce(f64::min)
+    }
+
+    /// The maximum value, or `None` if the field is empty.
+    pub fn max(&self) -> Option<f64> {
+        self.data.iter().copied().reduce(f64::max)
+    }
+
+    /// Values scaled into `[0, 1]` by the minimum and maximum of the finite values.
+    ///
+    /// A constant field gets all zeros. Infinite values, such as unreached pixels of
+    /// [`crate::edt_bounded`], are clamped to 0 or 1.
+    pub fn normalized(&self) -> Vec<f64> {
+        let (min, max) = self.finite_range().unwrap_or((0., 0.));
+        let range = if min < max { max - min } else { 1. };
+        self.data
+            .iter()
+            .map(|v| ((v - min) / range).clamp(0., 1.))
+            .collect()
+    }
+
+    /// Values scaled to 8 bits grayscale by the maximum finite value, e.g. for saving as an image.
+    ///
+    /// Infinite values get 255.
+    pub fn to_u8(&self) -> Vec<u8> {
+        let max = self
+            .finite_range()
+            .map_or(0., |(_, max)| max)
+            .max(f64::MIN_POSITIVE);
+        self.data.iter().map(|v| (v / max * 255.) as u8).collect()
+    }
+
+    /// The minimum and maximum of finite values, or `None` if there are none.
+    fn finite_range(&self) -> Option<(f64, f64)> {
+        self.data
+            .iter()
+            .copied()
+            .filter(|v| v.is_finite())
+            .fold(None, |range, v| match range {
+                Some((min, max)) => Some((v.min(min), v.max(max))),
+                None => Some((v, v)),
+            })
+    }
+
+    /// Unwrap the field values in row-major order.
+    pub fn into_vec(self) -> Vec<f64> {
+        self.data
+    }
+
+    /// Sample the field at continuous coordinates.
+    ///
+    /// An empty field is outside everywhere, so it returns the value of [`OutOfBounds::Constant`]
+    /// or NaN with [`OutOfBounds::Clamp`].
+    pub fn sample(&self, x: f64, y: f64) -> f64 {
+        self.interpolate(x, y).0
+    }
+
+    /// The gradient of the interpolated field at continuous coordinates.
+    pub fn gradient_at(&self, x: f64, y: f64) -> (f64, f64) {
+        let (_, dx, dy) = self.interpolate(x, y);
+        (dx, dy)
+    }
+
+    /// Returns the interpolated value and its partial derivatives.
+    fn interpolate(&self, x: f64, y: f64) -> (f64, f64, f64) {
+        if self.data.is_empty() {
+            // There is no border to clamp to.
+            let value = match self.out_of_bounds {
+                OutOfBounds::Clamp => f64::NAN,
+                OutOfBounds::Constant(value) => value,
+            };
+            return (value, 0., 0.);
+        }
+        let (max_x, max_y) = (
+            self.shape.0.saturating_sub(1) as f64,
+            self.shape.1.saturating_sub(1) as f64,
+        );
+        let outside_x = !(0. ..=max_x).contains(&x);
+        let outside_y = !(0. ..=max_y).contains(&y);
+        if let OutOfBounds::Constant(value) = self.out_of_bounds {
+            if outside_x || outside_y {
+                return (value, 0., 0.);
+            }
+        }
+        let (x, y) = (x.clamp(0., max_x), y.clamp(0., max_y));
+
+        let (x0, y0) = (x.floor(), y.floor());
+        let (wx, start) = self.weights(x - x0);
+        let (wy, _) = self.weights(y - y0);
+
+        let pixel = |dx: isize, dy: isize| {
+            let px = (x0 as isize + start + dx).clamp(0, max_x as isize) as usize;
+            let py = (y0 as isize + start + dy).clamp(0, max_y as isize) as usize;
+            self.data[px + py * self.shape.0]
+        };
+
+        let (mut value, mut grad_x, mut grad_y) = (0., 0., 0.);
+        for (j, (wy, dwy)) in wy.iter().enumerate() {
+            for (i, (wx, dwx)) in wx.iter().enumerate() {
+                let p = pixel(i as isize, j as isize);
+                value += wx * wy * p;
+                grad_x += dwx * wy * p;
+                grad_y += wx * dwy * p;
+            }
+        }
+
+        (
+            value,
+            if outside_x { 0. } else { grad_x },
+            if outside_y { 0. } else { grad_y },
+        )
+    }
+
+    /// Returns interpolation weights and their derivatives at fraction `t`,
+    /// and the offset of the first tap.
+    fn weights(&self, t: f64) -> ([(f64, f64); 4], isize) {
+        match self.interpolation {
+            Interpolation::Bilinear => ([(1. - t, -1.), (t, 1.), (0., 0.), (0., 0.)], 0),
+            Interpolation::Bicubic => {
+                let (t2, t3) = (t * t, t * t * t);
+                (
+                    [
+                        ((-t3 + 2. * t2 - t) / 2., (-3. * t2 + 4. * t - 1.) / 2.),
+                        ((3. * t3 - 5. * t2 + 2.) / 2., (9. * t2 - 10. * t) / 2.),
+                        ((-3. * t3 + 4. * t2 + t) / 2., (-9. * t2 + 8. * t + 1.) / 2.),
+                        ((t3 - t2) / 2., (3. * t2 - 2. * t) / 2.),
+                    ],
+                    -1,
+                )
+            }
+        }
+    }
+}
+
+impl Index<GridPos> for DistanceField {
+    type Output = f64;
+    fn index(&self, pos: GridPos) -> &Self::Output {
+        &self.data[pos.0 + pos.1 * self.shape.0]
+    }
+}
+
+impl IndexMut<GridPos> for DistanceField {
+    fn index_mut(&mut self, pos: GridPos) -> &mut Self::Output {
+        &mut self.data[pos.0 + pos.1 * self.shape.0]
+    }
+}
+
+/// Variant of [`crate::edt`] returning a [`DistanceField`].
+pub fn edt_field<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -> DistanceField {
+    DistanceField::new(edt(map, shape, invert), shape)
+}
+
+/// Variant of [`crate::edt_fmm`] returning a [`DistanceField`].
+pub fn edt_fmm_field<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -> DistanceField {
+    DistanceField::new(edt_fmm(map, shape, invert), shape)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn plane() -> DistanceField {
+        let shape = (6, 5);
+        DistanceField::new(
+            (0..30)
+                .map(|i| (i % shape.0) as f64 + 2. * (i / shape.0) as f64)
+                .collect(),
+            shape,
+        )
+    }
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "a: {}, b: {}", a, b);
+    }
+
+    #[test]
+    fn test_sample() {
+        for interpolation in [Interpolation::Bilinear, Interpolation::Bicubic] {
+            let field = plane().with_interpolation(interpolation);
+            assert_close(field.sample(2.25, 1.5), 5.25);
+            assert_close(field.sample(3., 2.), 7.);
+            let (dx, dy) = field.gradient_at(2.25, 1.5);
+            assert_close(dx, 1.);
+            assert_close(dy, 2.);
+        }
+    }
+
+    #[test]
+    fn test_out_of_bounds() {
+        let field = plane();
+        assert_eq!(field.sample(-3., 1.), 2.);
+        assert_eq!(field.gradient_at(-3., 1.5), (0., 2.));
+        assert_eq!(field.sample(5., 4.), 13.);
+        let field = field.with_out_of_bounds(OutOfBounds::Constant(-1.));
+        assert_eq!(field.sample(5.5, 1.), -1.);
+        assert_eq!(field.gradient_at(5.5, 1.), (0., 0.));
+    }
+
+    #[test]
+    fn test_empty() {
+        for shape in [(0, 0), (3, 0), (0, 2)] {
+            let field = DistanceField::new(vec![], shape);
+            assert!(field.sample(0., 0.).is_nan());
+            assert_eq!(field.gradient_at(0., 0.), (0., 0.));
+            let field = field.with_out_of_bounds(OutOfBounds::Constant(-1.));
+            assert_eq!(field.sample(0.5, 0.), -1.);
+            assert_eq!(field.gradient_at(0.5, 0.), (0., 0.));
+        }
+    }
+
+    #[test]
+    fn test_container() {
+        let mut field = plane();
+        assert_eq!((field.width(), field.height()), (6, 5));
+        assert_eq!(field[(2, 1)], 4.);
+        assert_eq!(field.row(1), [2., 3., 4., 5., 6., 7.]);
+        assert_eq!(field.rows().count(), 5);
+        assert_eq!((field.min(), field.max()), (Some(0.), Some(13.)));
+        field[(0, 0)] = 26.;
+        assert_eq!(field.normalized()[0], 1.);
+        assert_eq!(field.normalized()[1], 0.);
+        let u8_field = field.to_u8();
+        assert_eq!(u8_field[0], 255);
+        assert_eq!(u8_field[5 + 4 * 6], 127);
+    }
+
+    #[test]
+    fn test_bounded_range() {
+        let shape = (30, 20);
+        let mut map = vec![true; shape.0 * shape.1];
+        map[5 + 7 * shape.0] = false;
+        let field = DistanceField::new(crate::edt_bounded(&map, shape, false, 3.), shape);
+        assert_eq!(field.max(), Some(f64::INFINITY));
+
+        let u8_field = field.to_u8();
+        assert_eq!(u8_field[5 + 7 * shape.0], 0);
+        assert_eq!(u8_field[8 + 7 * shape.0], 255);
+        assert_eq!(u8_field[7 + 7 * shape.0], 170);
+        assert_eq!(u8_field[15 + 10 * shape.0], 255);
+
+        let normalized = field.normalized();
+        assert_eq!(normalized[6 + 7 * shape.0], 1. / 3.);
+        assert_eq!(normalized[15 + 10 * shape.0], 1.);
+    }
+}
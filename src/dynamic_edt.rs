@@ -0,0 +1,284 @@
+use super::{exact_edt::feature_transform_by, BoolLike, GridPos};
+use std::{
+    cmp::Reverse,
+    collections::{BinaryHeap, HashMap},
+};
+
+/// A distance map that can be updated incrementally when obstacles change.
+///
+/// It implements the dynamic brushfire algorithm by Lau et al. \[2013\]:
+/// each cell remembers its nearest obstacle, and a change only propagates a "raise" wave through
+/// the cells that referred to a removed obstacle and a "lower" wave from new or remaining
+/// obstacles, so only the affected region is visited.
+///
+/// Unlike [`crate::edt`], the outside of the map is not regarded as an obstacle, and cells get
+/// infinity if there are no obstacles at all.
+///
+/// Lau, B., Sprunk, C. and Burgard, W. "Efficient grid-based spatial representations for robot
+/// navigation in dynamic environments." Robotics and Autonomous Systems 61(10), 2013.
+pub struct DynamicEdt {
+    shape: (usize, usize),
+    obstacle: Vec<bool>,
+    nearest: Vec<Option<GridPos>>,
+    /// Squared distance to `nearest`, or `u64::MAX` if there is none.
+    dist_sq: Vec<u64>,
+    raise: Vec<bool>,
+    open: BinaryHeap<Reverse<(u64, usize)>>,
+}
+
+impl DynamicEdt {
+    /// Create a distance map from a binary image.
+    ///
+    /// It assumes zero pixels are obstacles. If you want to invert the logic,
+    /// put `true` to the third argument.
+    pub fn new<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -> Self {
+        let is_fg = |(x, y): GridPos| map[x + y * shape.0].as_bool() != invert;
+        let nearest = feature_transform_by(shape, is_fg);
+        let dist_sq = nearest
+            .iter()
+            .enumerate()
+            .map(|(i, n)| match n {
+                Some(n) => dist_sq((i % shape.0, i / shape.0), *n),
+                None => u64::MAX,
+            })
+            .collect();
+        Self {
+            shape,
+            obstacle: (0..map.len())
+                .map(|i| !is_fg((i % shape.0, i / shape.0)))
+                .collect(),
+            nearest,
+            dist_sq,
+            raise: vec![false; map.len()],
+            open: BinaryHeap::new(),
+        }
+    }
+
+    /// The dimensions of the map.
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    /// Whether the cell at `pos` is an obstacle.
+    pub fn is_obstacle(&self, pos: GridPos) -> bool {
+        self.obstacle[self.index(pos)]
+    }
+
+    /// The distance from `pos` to the nearest obstacle.
+    pub fn distance(&self, pos: GridPos) -> f64 {
+        to_distance(self.dist_sq[self.index(pos)])
+    }
+
+    /// The position of the nearest obstacle from `pos`, if any.
+    pub fn nearest_obstacle(&self, pos: GridPos) -> Option<GridPos> {
+        self.nearest[self.index(pos)]
+    }
+
+    /// The distances of all cells in the same layout as [`crate::edt`].
+    pub fn distances(&self) -> Vec<f64> {
+        self.dist_sq.iter().copied().map(to_distance).collect()
+    }
+
+    /// Put an obstacle at `pos` and update the map.
+    ///
+    /// Returns the cells whose distance has changed.
+    pub fn set_obstacle(&mut self, pos: GridPos) -> Vec<GridPos> {
+        let i = self.index(pos);
+        if self.obstacle[i] {
+            return vec![];
+        }
+        let mut changes = HashMap::new();
+        self.obstacle[i] = true;
+        self.set_cell(&mut changes, i, Some(pos), 0);
+        self.raise[i] = false;
+        self.open.push(Reverse((0, i)));
+        self.update(changes)
+    }
+
+    /// Remove the obstacle at `pos` and update the map.
+    ///
+    /// Returns the cells whose distance has changed.
+    pub fn clear_obstacle(&mut self, pos: GridPos) -> Vec<GridPos> {
+        let i = self.index(pos);
+        if !self.obstacle[i] {
+            return vec![];
+        }
+        let mut changes = HashMap::new();
+        self.obstacle[i] = false;
+        self.set_cell(&mut changes, i, None, u64::MAX);
+        self.raise[i] = true;
+        self.open.push(Reverse((0, i)));
+        self.update(changes)
+    }
+
+    fn update(&mut self, mut changes: HashMap<usize, u64>) -> Vec<GridPos> {
+        while let Some(Reverse((_, i))) = self.open.pop() {
+            if self.raise[i] {
+                self.raise_cell(&mut changes, i);
+            } else if let Some(n) = self.nearest[i] {
+                if self.obstacle[self.index(n)] {
+                    self.lower_cell(&mut changes, i, n);
+                }
+            }
+        }
+
+        let mut ret: Vec<_> = changes
+            .into_iter()
+            .filter(|(i, old)| self.dist_sq[*i] != *old)
+            .map(|(i, _)| (i % self.shape.0, i / self.shape.0))
+            .collect();
+        ret.sort_by_key(|(x, y)| (*y, *x));
+        ret
+    }
+
+    /// Invalidate the neighbors that refer to removed obstacles, and queue the others so that
+    /// they can fill the invalidated region.
+    fn raise_cell(&mut self, changes: &mut HashMap<usize, u64>, i: usize) {
+        for n in self.neighbors(i) {
+            let nearest = match self.nearest[n] {
+                Some(nearest) => nearest,
+                None => continue,
+            };
+            if self.raise[n] {
+                continue;
+            }
+            self.open.push(Reverse((self.dist_sq[n], n)));
+            if !self.obstacle[self.index(nearest)] {
+                self.set_cell(changes, n, None, u64::MAX);
+                self.raise[n] = true;
+            }
+        }
+        self.raise[i] = false;
+    }
+
+    /// Propagate the obstacle `nearest` of cell `i` to the neighbors if it is closer.
+    fn lower_cell(&mut self, changes: &mut HashMap<usize, u64>, i: usize, nearest: GridPos) {
+        for n in self.neighbors(i) {
+            if self.raise[n] {
+                continue;
+            }
+            let d = dist_sq((n % self.shape.0, n / self.shape.0), nearest);
+            if d < self.dist_sq[n] {
+                self.set_cell(changes, n, Some(nearest), d);
+                self.open.push(Reverse((d, n)));
+            }
+        }
+    }
+
+    fn set_cell(
+        &mut self,
+        changes: &mut HashMap<usize, u64>,
+        i: usize,
+        nearest: Option<GridPos>,
+        dist_sq: u64,
+    ) {
+        changes.entry(i).or_insert(self.dist_sq[i]);
+        self.nearest[i] = nearest;
+        self.dist_sq[i] = dist_sq;
+    }
+
+    /// Indices of the 8-neighbors of the cell at index `i`.
+    fn neighbors(&self, i: usize) -> impl Iterator<Item = usize> {
+        let (w, h) = (self.shape.0 as isize, self.shape.1 as isize);
+        let (x, y) = ((i % self.shape.0) as isize, (i / self.shape.0) as isize);
+        (-1..=1)
+            .flat_map(move |dy| (-1..=1).map(move |dx| (x + dx, y + dy)))
+            .filter(move |(nx, ny)| {
+                (*nx, *ny) != (x, y) && 0 <= *nx && *nx < w && 0 <= *ny && *ny < h
+            })
+            .map(move |(nx, ny)| (nx + ny * w) as usize)
+    }
+
+    fn index(&self, pos: GridPos) -> usize {
+        pos.0 + pos.1 * self.shape.0
+    }
+}
+
+fn dist_sq(a: GridPos, b: GridPos) -> u64 {
+    let dx = a.0 as i64 - b.0 as i64;
+    let dy = a.1 as i64 - b.1 as i64;
+    (dx * dx + dy * dy) as u64
+}
+
+fn to_distance(dist_sq: u64) -> f64 {
+    if dist_sq == u64::MAX {
+        f64::INFINITY
+    } else {
+        (dist_sq as f64).sqrt()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::exact_edt::edt_sq_by;
+
+    fn exact(dyn_edt: &DynamicEdt) -> Vec<f64> {
+        let shape = dyn_edt.shape();
+        edt_sq_by(shape, |pos| !dyn_edt.is_obstacle(pos), false)
+            .into_iter()
+            .map(f64::sqrt)
+            .collect()
+    }
+
+    #[test]
+    fn test_set_clear() {
+        let shape = (12, 9);
+        let mut map = vec![true; shape.0 * shape.1];
+        map[2 + 2 * shape.0] = false;
+        let mut dyn_edt = DynamicEdt::new(&map, shape, false);
+        assert_eq!(dyn_edt.distances(), exact(&dyn_edt));
+
+        let changed = dyn_edt.set_obstacle((9, 6));
+        assert_eq!(dyn_edt.distances(), exact(&dyn_edt));
+        assert!(changed.contains(&(9, 6)));
+        assert!(changed.contains(&(10, 7)));
+        assert!(!changed.contains(&(2, 3)));
+        assert_eq!(dyn_edt.nearest_obstacle((11, 8)), Some((9, 6)));
+
+        let changed = dyn_edt.clear_obstacle((2, 2));
+        assert_eq!(dyn_edt.distances(), exact(&dyn_edt));
+        assert!(changed.contains(&(0, 0)));
+        assert!(!changed.contains(&(10, 6)));
+
+        dyn_edt.clear_obstacle((9, 6));
+        assert!(dyn_edt.distances().iter().all(|d| d.is_infinite()));
+        assert!(dyn_edt.set_obstacle((9, 6)).len() == shape.0 * shape.1);
+    }
+
+    #[test]
+    fn test_random_updates() {
+        let shape = (24, 16);
+        let mut dyn_edt = DynamicEdt::new(&vec![1u8; shape.0 * shape.1], shape, false);
+        // A simple linear congruential generator to avoid depending on rand crate
+        let mut seed = 12345u64;
+        let mut rand = |n: usize| {
+            seed = seed
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            (seed >> 33) as usize % n
+        };
+        for _ in 0..200 {
+            let pos = (rand(shape.0), rand(shape.1));
+            let before = dyn_edt.distances();
+            let changed = if dyn_edt.is_obstacle(pos) {
+                dyn_edt.clear_obstacle(pos)
+            } else {
+                dyn_edt.set_obstacle(pos)
+            };
+            let after = dyn_edt.distances();
+            for (a, b) in after.iter().zip(exact(&dyn_edt)) {
+                assert!(
+                    a == &b || a.is_infinite() && b.is_infinite(),
+                    "a: {}, b: {}",
+                    a,
+                    b
+                );
+            }
+            for (i, (before, after)) in before.iter().zip(&after).enumerate() {
+                let pos = (i % shape.0, i / shape.0);
+                assert_eq!(before != after, changed.contains(&pos));
+            }
+        }
+    }
+}
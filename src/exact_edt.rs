@@ -1,4 +1,4 @@
-use super::BoolLike;
+use super::{BoolLike, GridPos};
 
 /// Produce an EDT from binary image.
 ///
@@ -8,11 +8,7 @@ use super::BoolLike;
 /// It assumes zero pixels are obstacles. If you want to invert the logic,
 /// put `true` to the third argument.
 pub fn edt<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -> Vec<f64> {
-    let mut ret = edt_sq(map, shape, invert);
-    for pixel in &mut ret {
-        *pixel = pixel.sqrt();
-    }
-    ret
+    edt_with(map, shape, |p| p.as_bool() != invert)
 }
 
 /// Squared EDT of a given image.
@@ -21,18 +17,229 @@ pub fn edt<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -> Vec<f
 ///
 /// It is more efficient if you only need squared edt, because you wouldn't need to compute square root.
 pub fn edt_sq<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -> Vec<f64> {
-    let horz_edt = horizontal_edt(map, shape, invert);
+    edt_sq_with(map, shape, |p| p.as_bool() != invert)
+}
+
+/// Produce an EDT from an image of any pixel type, using a predicate to tell foreground pixels.
+///
+/// The predicate should return `true` for pixels whose distance should be computed, and `false`
+/// for obstacles. For example, you can threshold a grayscale image like
+/// `edt_with(&img, shape, |p| 128 < *p)` without allocating a `Vec<bool>`.
+pub fn edt_with<T>(map: &[T], shape: (usize, usize), predicate: impl Fn(&T) -> bool) -> Vec<f64> {
+    let mut ret = edt_sq_with(map, shape, predicate);
+    for pixel in &mut ret {
+        *pixel = pixel.sqrt();
+    }
+    ret
+}
+
+/// Squared EDT with a foreground predicate.
+///
+/// The interface is equivalent to [`edt_with`], but it returns squared EDT.
+pub fn edt_sq_with<T>(
+    map: &[T],
+    shape: (usize, usize),
+    predicate: impl Fn(&T) -> bool,
+) -> Vec<f64> {
+    edt_sq_by(shape, |(x, y)| predicate(&map[x + y * shape.0]), true)
+}
+
+/// EDT with a maximum distance.
+///
+/// It is equivalent to [`edt`], except that pixels farther than `max_distance` from obstacles
+/// get `f64::INFINITY` as a sentinel. Since the vertical pass only has to scan rows within
+/// `max_distance`, it is much faster on sparse maps if the bound is small.
+/// A negative `max_distance` is regarded as zero, leaving only obstacles finite.
+pub fn edt_bounded<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    max_distance: f64,
+) -> Vec<f64> {
+    let mut ret = edt_sq_bounded(map, shape, invert, max_distance);
+    for pixel in &mut ret {
+        *pixel = pixel.sqrt();
+    }
+    ret
+}
+
+/// Squared EDT with a maximum distance.
+///
+/// The interface is equivalent to [`edt_bounded`], but it returns squared EDT.
+/// Note that `max_distance` is not squared.
+pub fn edt_sq_bounded<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    max_distance: f64,
+) -> Vec<f64> {
+    edt_sq_by_bounded(
+        shape,
+        |(x, y)| map[x + y * shape.0].as_bool() != invert,
+        true,
+        max_distance,
+    )
+}
+
+/// The pass of the exact EDT in progress, given to the callback of [`edt_cb`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum EdtPass {
+    /// The horizontal pass, which processes the image row by row.
+    Horizontal,
+    /// The vertical pass, which processes the image column by column.
+    Vertical,
+}
+
+#[non_exhaustive]
+/// A type that will be given as the argument to the callback with [`edt_cb`].
+///
+/// It has `non_exhaustive` attribute so that the library can add more arguments in
+/// the future.
+pub struct EdtCallbackData<'src> {
+    /// The pass in progress.
+    pub pass: EdtPass,
+    /// The number of rows (in the horizontal pass) or columns (in the vertical pass) finished.
+    pub completed: usize,
+    /// The total number of rows or columns in the current pass.
+    pub total: usize,
+    /// The buffer of the current pass in progress. In the horizontal pass, it contains
+    /// horizontal (not squared) distances; in the vertical pass, squared EDT.
+    pub map: &'src [f64],
+}
 
-    let vertical_scan = |x, y| {
-        let total_edt = (0..shape.1).map(|y2| {
+impl<'src> EdtCallbackData<'src> {
+    /// The overall progress in the range of `[0, 1]`, counting both passes evenly.
+    pub fn progress(&self) -> f64 {
+        let pass_progress = if self.total == 0 {
+            1.
+        } else {
+            self.completed as f64 / self.total as f64
+        };
+        match self.pass {
+            EdtPass::Horizontal => pass_progress / 2.,
+            EdtPass::Vertical => 0.5 + pass_progress / 2.,
+        }
+    }
+}
+
+/// EDT with a callback.
+///
+/// The callback is called after each row in the horizontal pass and each column in the vertical
+/// pass. It can terminate the process by returning false, in which case `None` is returned.
+/// See [`edt`] for the other arguments.
+pub fn edt_cb<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    callback: impl FnMut(EdtCallbackData) -> bool,
+) -> Option<Vec<f64>> {
+    let mut ret = edt_sq_cb(map, shape, invert, callback)?;
+    for pixel in &mut ret {
+        *pixel = pixel.sqrt();
+    }
+    Some(ret)
+}
+
+/// Squared EDT with a callback.
+///
+/// The interface is equivalent to [`edt_cb`], but it returns squared EDT.
+pub fn edt_sq_cb<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    mut callback: impl FnMut(EdtCallbackData) -> bool,
+) -> Option<Vec<f64>> {
+    let horz_edt = horizontal_edt_cb(
+        shape,
+        |(x, y)| map[x + y * shape.0].as_bool() != invert,
+        true,
+        |completed, map| {
+            callback(EdtCallbackData {
+                pass: EdtPass::Horizontal,
+                completed,
+                total: shape.1,
+                map,
+            })
+        },
+    )?;
+    vertical_edt_sq_cb(shape, &horz_edt, true, f64::INFINITY, |completed, map| {
+        callback(EdtCallbackData {
+            pass: EdtPass::Vertical,
+            completed,
+            total: shape.0,
+            map,
+        })
+    })
+}
+
+/// Squared EDT of an image given by a function that returns whether a pixel is foreground.
+///
+/// If `border` is true, the outside of the image is regarded as an obstacle like [`edt`].
+/// Otherwise, foreground pixels get infinity if there is no obstacle in the image.
+pub(crate) fn edt_sq_by(
+    shape: (usize, usize),
+    is_fg: impl Fn(GridPos) -> bool,
+    border: bool,
+) -> Vec<f64> {
+    edt_sq_by_bounded(shape, is_fg, border, f64::INFINITY)
+}
+
+/// [`edt_sq_by`] with pixels farther than `max_distance` set to infinity.
+pub(crate) fn edt_sq_by_bounded(
+    shape: (usize, usize),
+    is_fg: impl Fn(GridPos) -> bool,
+    border: bool,
+    max_distance: f64,
+) -> Vec<f64> {
+    let horz_edt = horizontal_edt(shape, is_fg, border);
+    vertical_edt_sq(shape, &horz_edt, border, max_distance)
+}
+
+/// The vertical pass of the squared EDT, given the output of [`horizontal_edt`].
+pub(crate) fn vertical_edt_sq(
+    shape: (usize, usize),
+    horz_edt: &[f64],
+    border: bool,
+    max_distance: f64,
+) -> Vec<f64> {
+    vertical_edt_sq_cb(shape, horz_edt, border, max_distance, |_, _| true).unwrap()
+}
+
+/// [`vertical_edt_sq`] that calls `callback` with the number of finished columns and the output
+/// buffer after each column. Returns `None` if the callback returns false.
+fn vertical_edt_sq_cb(
+    shape: (usize, usize),
+    horz_edt: &[f64],
+    border: bool,
+    max_distance: f64,
+    mut callback: impl FnMut(usize, &[f64]) -> bool,
+) -> Option<Vec<f64>> {
+    let max_distance = max_distance.max(0.);
+    let max_sq = max_distance.powf(2.);
+    let range = if max_distance < shape.1 as f64 {
+        max_distance as usize
+    } else {
+        shape.1
+    };
+
+    let vertical_scan = |x, y: usize| {
+        let total_edt = (y.saturating_sub(range)..(y + range + 1).min(shape.1)).map(|y2| {
             let horz_val: f64 = horz_edt[x + y2 * shape.0];
             (y2 as f64 - y as f64).powf(2.) + horz_val.powf(2.)
         });
-        total_edt
-            .reduce(f64::min)
-            .unwrap()
-            .min((y as f64).powf(2.))
-            .min(((shape.1 - y) as f64).powf(2.))
+        let min_edt = total_edt.reduce(f64::min).unwrap();
+        let min_edt = if border {
+            min_edt
+                .min(((y + 1) as f64).powf(2.))
+                .min(((shape.1 - y) as f64).powf(2.))
+        } else {
+            min_edt
+        };
+        if min_edt <= max_sq {
+            min_edt
+        } else {
+            f64::INFINITY
+        }
     };
 
     let mut ret = vec![0.; shape.0 * shape.1];
@@ -41,16 +248,40 @@ pub fn edt_sq<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -> Ve
         for y in 0..shape.1 {
             ret[x + y * shape.0] = vertical_scan(x, y);
         }
+        if !callback(x + 1, &ret) {
+            return None;
+        }
     }
 
-    ret
+    Some(ret)
 }
 
-fn horizontal_edt<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -> Vec<f64> {
-    let mut horz_edt = map
-        .iter()
-        .map(|b| (((b.as_bool() != invert) as usize) * map.len()) as f64)
+pub(crate) fn horizontal_edt(
+    shape: (usize, usize),
+    is_fg: impl Fn(GridPos) -> bool,
+    border: bool,
+) -> Vec<f64> {
+    horizontal_edt_cb(shape, is_fg, border, |_, _| true).unwrap()
+}
+
+/// [`horizontal_edt`] that calls `callback` with the number of finished rows and the output
+/// buffer after each row. Returns `None` if the callback returns false.
+fn horizontal_edt_cb(
+    shape: (usize, usize),
+    is_fg: impl Fn(GridPos) -> bool,
+    border: bool,
+    mut callback: impl FnMut(usize, &[f64]) -> bool,
+) -> Option<Vec<f64>> {
+    let mut horz_edt = (0..shape.0 * shape.1)
+        .map(|i| {
+            if is_fg((i % shape.0, i / shape.0)) {
+                f64::INFINITY
+            } else {
+                0.
+            }
+        })
         .collect::<Vec<f64>>();
+    let border_val = if border { 0. } else { f64::INFINITY };
 
     let scan = |x, y, min_val: &mut f64, horz_edt: &mut Vec<f64>| {
         let f: f64 = horz_edt[x + y * shape.0];
@@ -61,17 +292,95 @@ fn horizontal_edt<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -
     };
 
     for y in 0..shape.1 {
-        let mut min_val = 0.;
+        let mut min_val = border_val;
         for x in 0..shape.0 {
             scan(x, y, &mut min_val, &mut horz_edt);
         }
-        min_val = 0.;
+        min_val = border_val;
         for x in (0..shape.0).rev() {
             scan(x, y, &mut min_val, &mut horz_edt);
         }
+        if !callback(y + 1, &horz_edt) {
+            return None;
+        }
     }
 
-    horz_edt
+    Some(horz_edt)
+}
+
+/// Feature transform of a given image.
+///
+/// The returned vec has the same size as the input slice, containing the position of the
+/// nearest obstacle pixel (in Euclidean sense) for each pixel, or `None` if there is no obstacle
+/// at all. Obstacle pixels refer to themselves.
+///
+/// Unlike [`edt`], the outside of the image is not regarded as an obstacle, because there is no
+/// pixel to point to.
+pub fn feature_transform<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+) -> Vec<Option<GridPos>> {
+    feature_transform_by(shape, |(x, y)| map[x + y * shape.0].as_bool() != invert)
+}
+
+/// Feature transform of an image given by a function that returns whether a pixel is foreground.
+pub(crate) fn feature_transform_by(
+    shape: (usize, usize),
+    is_fg: impl Fn(GridPos) -> bool,
+) -> Vec<Option<GridPos>> {
+    let horz_ft = horizontal_ft(shape, is_fg);
+
+    let vertical_scan = |x: usize, y: usize| {
+        let mut best: Option<(f64, GridPos)> = None;
+        for y2 in 0..shape.1 {
+            if let Some(fx) = horz_ft[x + y2 * shape.0] {
+                let dist = (y2 as f64 - y as f64).powf(2.) + (fx as f64 - x as f64).powf(2.);
+                if best.map(|(best_dist, _)| dist < best_dist).unwrap_or(true) {
+                    best = Some((dist, (fx, y2)));
+                }
+            }
+        }
+        best.map(|(_, pos)| pos)
+    };
+
+    let mut ret = vec![None; shape.0 * shape.1];
+
+    for x in 0..shape.0 {
+        for y in 0..shape.1 {
+            ret[x + y * shape.0] = vertical_scan(x, y);
+        }
+    }
+
+    ret
+}
+
+/// Returns the x coordinate of the nearest obstacle in the same row for each pixel.
+fn horizontal_ft(shape: (usize, usize), is_fg: impl Fn(GridPos) -> bool) -> Vec<Option<usize>> {
+    let mut horz_ft = vec![None; shape.0 * shape.1];
+
+    for y in 0..shape.1 {
+        let mut last = None;
+        for x in 0..shape.0 {
+            if !is_fg((x, y)) {
+                last = Some(x);
+            }
+            horz_ft[x + y * shape.0] = last;
+        }
+        last = None;
+        for x in (0..shape.0).rev() {
+            let cell = &mut horz_ft[x + y * shape.0];
+            if *cell == Some(x) {
+                last = Some(x);
+            } else if let Some(right) = last {
+                if cell.map(|left| right - x < x - left).unwrap_or(true) {
+                    *cell = Some(right);
+                }
+            }
+        }
+    }
+
+    horz_ft
 }
 
 #[cfg(test)]
@@ -89,12 +398,13 @@ mod test {
             "0012332100",
             "0001221000",
         ];
+        let shape = (map.len() / str_edt.len(), str_edt.len());
         print_2d(&reshape(
-            &horizontal_edt(&map, (map.len() / str_edt.len(), str_edt.len()), false),
-            (str_edt[0].len(), str_edt.len()),
+            &horizontal_edt(shape, |(x, y)| map[x + y * shape.0], true),
+            shape,
         ));
         assert_eq!(
-            horizontal_edt(&map, (map.len() / str_edt.len(), str_edt.len()), false),
+            horizontal_edt(shape, |(x, y)| map[x + y * shape.0], true),
             parse_edt_str(&str_edt)
         );
     }
@@ -115,4 +425,92 @@ mod test {
         print_2d(&reshape(&edt, shape));
         assert_eq!(edt, parse_edt_str(&str_edt));
     }
+
+    #[test]
+    fn test_border() {
+        // The outside of the image is an obstacle on every side, including the top.
+        let str_edt = ["111", "141", "111"];
+        assert_eq!(edt_sq(&[true; 9], (3, 3), false), parse_edt_str(&str_edt));
+    }
+
+    #[test]
+    fn test_bounded() {
+        let shape = (30, 20);
+        let map = sparse_map(shape);
+        let full = edt_sq_by(shape, |(x, y)| map[x + y * shape.0], false);
+        let bounded = edt_sq_by_bounded(shape, |(x, y)| map[x + y * shape.0], false, 4.5);
+        for (full, bounded) in full.iter().zip(&bounded) {
+            if *full <= 4.5 * 4.5 {
+                assert_eq!(full, bounded);
+            } else {
+                assert_eq!(*bounded, f64::INFINITY);
+            }
+        }
+        assert_eq!(
+            edt_bounded(&[true; 9], (3, 3), false, -1.),
+            vec![f64::INFINITY; 9]
+        );
+        assert_eq!(
+            edt_bounded(&map, shape, false, 100.),
+            edt(&map, shape, false)
+        );
+    }
+
+    #[test]
+    fn test_edt_cb() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        let mut progress = vec![];
+        let ret = edt_sq_cb(&map, shape, false, |data| {
+            progress.push((data.pass, data.completed, data.progress()));
+            assert_eq!(data.map.len(), map.len());
+            true
+        });
+        assert_eq!(ret, Some(edt_sq(&map, shape, false)));
+        assert_eq!(progress.len(), shape.0 + shape.1);
+        assert_eq!(progress[4], (EdtPass::Horizontal, 5, 0.5));
+        assert_eq!(progress[5], (EdtPass::Vertical, 1, 0.55));
+        assert_eq!(progress.last(), Some(&(EdtPass::Vertical, shape.0, 1.)));
+
+        let mut calls = 0;
+        let ret = edt_cb(&map, shape, false, |data| {
+            calls += 1;
+            data.pass == EdtPass::Horizontal
+        });
+        assert_eq!(ret, None);
+        assert_eq!(calls, shape.1 + 1);
+    }
+
+    #[test]
+    fn test_edt_with() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        // Faint noise below the threshold should not become obstacles.
+        let luma: Vec<u8> = map.iter().map(|b| if *b { 200 } else { 30 }).collect();
+        assert_eq!(
+            edt_sq_with(&luma, shape, |p| 128 < *p),
+            edt_sq(&map, shape, false)
+        );
+    }
+
+    #[test]
+    fn test_feature_transform() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        let ft = feature_transform(&map, shape, false);
+        let dist_sq = |(ax, ay): GridPos, (bx, by): GridPos| {
+            (ax as f64 - bx as f64).powf(2.) + (ay as f64 - by as f64).powf(2.)
+        };
+        for y in 0..shape.1 {
+            for x in 0..shape.0 {
+                let brute = (0..map.len())
+                    .filter(|i| !map[*i])
+                    .map(|i| dist_sq((x, y), (i % shape.0, i / shape.0)))
+                    .reduce(f64::min);
+                let feature = ft[x + y * shape.0].unwrap();
+                assert!(!map[feature.0 + feature.1 * shape.0]);
+                assert_eq!(Some(dist_sq((x, y), feature)), brute);
+            }
+        }
+    }
 }
@@ -8,14 +8,27 @@ use std::{
 /// Shorthand function for EDT using Fast Marching method.
 ///
 /// Fast Marching method is inexact, but much faster algorithm to compute EDT especially for large images.
+/// Like the standard Fast Marching method, a pixel's distance is fixed once it is accepted from the
+/// wavefront and is not lowered by pixels accepted later.
 pub fn edt_fmm<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -> Vec<f64> {
-    let mut grid = Grid {
-        storage: map
-            .iter()
-            .map(|b| ((b.as_bool() != invert) as usize) as f64)
-            .collect::<Vec<f64>>(),
-        dims: shape,
-    };
+    edt_fmm_with(map, shape, |p| p.as_bool() != invert)
+}
+
+/// EDT using Fast Marching method with a foreground predicate.
+///
+/// The predicate should return `true` for pixels whose distance should be computed.
+/// See [`crate::edt_with`] for details.
+pub fn edt_fmm_with<T>(
+    map: &[T],
+    shape: (usize, usize),
+    predicate: impl Fn(&T) -> bool,
+) -> Vec<f64> {
+    edt_fmm_by(shape, |(x, y)| predicate(&map[x + y * shape.0]))
+}
+
+/// EDT using Fast Marching method of an image given by a function that returns whether a pixel is foreground.
+pub(crate) fn edt_fmm_by(shape: (usize, usize), is_fg: impl Fn(GridPos) -> bool) -> Vec<f64> {
+    let mut grid = Grid::from_fn(shape, is_fg);
     let mut fast_marcher = FastMarcher::new_from_map(&grid, shape);
 
     fast_marcher.evolve(&mut grid);
@@ -23,6 +36,83 @@ pub fn edt_fmm<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool) -> V
     grid.storage
 }
 
+/// EDT using Fast Marching method with a maximum distance.
+///
+/// The marcher stops once the wavefront goes beyond `max_distance`, and the pixels that are not
+/// reached by then get `f64::INFINITY` as a sentinel. It gives large speedups on sparse maps if
+/// the bound is small, e.g. for inflation of costmaps.
+/// A negative `max_distance` is regarded as zero, leaving only obstacles finite.
+pub fn edt_fmm_bounded<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    max_distance: f64,
+) -> Vec<f64> {
+    let mut grid = Grid::from_fn(shape, |(x, y)| map[x + y * shape.0].as_bool() != invert);
+    let mut fast_marcher = FastMarcher::new_from_map(&grid, shape);
+
+    fast_marcher.evolve_bounded(&mut grid, max_distance);
+
+    for (pixel, visited) in grid.storage.iter_mut().zip(&fast_marcher.visited) {
+        if *pixel != 0. && (*visited == 0. || max_distance < *visited) {
+            *pixel = f64::INFINITY;
+        }
+    }
+
+    grid.storage
+}
+
+/// Geodesic Voronoi tessellation using Fast Marching method.
+///
+/// `seeds` gives the seed index of each pixel, and `is_passable` tells whether the wavefront can
+/// go through a pixel. Returns the distance to and the index of the nearest seed for each pixel.
+pub(crate) fn voronoi_fmm_by(
+    shape: (usize, usize),
+    seeds: &[Option<usize>],
+    is_passable: impl Fn(GridPos) -> bool,
+) -> (Vec<f64>, Vec<Option<usize>>) {
+    let mut grid = Grid::from_fn(shape, |pos| {
+        seeds[pos.0 + pos.1 * shape.0].is_none() && is_passable(pos)
+    });
+
+    let seed_neighbor = |(x, y): GridPos| {
+        let neighbors = [
+            (x.wrapping_sub(1), y),
+            (x, y.wrapping_sub(1)),
+            (x + 1, y),
+            (x, y + 1),
+        ];
+        neighbors
+            .iter()
+            .filter(|(x, y)| *x < shape.0 && *y < shape.1)
+            .find_map(|(x, y)| seeds[x + y * shape.0])
+    };
+    let initial: Vec<_> = (0..shape.1)
+        .flat_map(|y| (0..shape.0).map(move |x| (x, y)))
+        .filter(|pos| grid[*pos] != 0.)
+        .filter_map(|pos| Some((pos, seed_neighbor(pos)?)))
+        .collect();
+
+    let mut fast_marcher = FastMarcher::new_labeled(initial.into_iter(), shape);
+    fast_marcher.evolve(&mut grid);
+
+    let FastMarcher {
+        visited, labels, ..
+    } = fast_marcher;
+    let labels = labels.unwrap_or_default();
+    (0..shape.0 * shape.1)
+        .map(|i| {
+            if let Some(seed) = seeds[i] {
+                (0., Some(seed))
+            } else if visited[i] != 0. {
+                (visited[i], Some(labels[i]))
+            } else {
+                (f64::INFINITY, None)
+            }
+        })
+        .unzip()
+}
+
 /// EDT with Fast Marching method with a callback.
 ///
 /// The callback can terminate the process by returning false.
@@ -32,13 +122,7 @@ pub fn edt_fmm_cb<T: BoolLike>(
     invert: bool,
     callback: impl FnMut(FMMCallbackData) -> bool,
 ) -> Vec<f64> {
-    let mut grid = Grid {
-        storage: map
-            .iter()
-            .map(|b| ((b.as_bool() != invert) as usize) as f64)
-            .collect::<Vec<f64>>(),
-        dims: shape,
-    };
+    let mut grid = Grid::from_fn(shape, |(x, y)| map[x + y * shape.0].as_bool() != invert);
     let mut fast_marcher = FastMarcher::new_from_map(&grid, shape);
 
     fast_marcher.evolve_cb(&mut grid, callback);
@@ -55,6 +139,13 @@ pub(super) struct Grid {
 }
 
 impl Grid {
+    pub(super) fn from_fn(dims: (usize, usize), is_fg: impl Fn(GridPos) -> bool) -> Self {
+        let storage = (0..dims.0 * dims.1)
+            .map(|i| (is_fg((i % dims.0, i / dims.0)) as usize) as f64)
+            .collect();
+        Self { storage, dims }
+    }
+
     pub(super) fn find_boundary(&self) -> Vec<GridPos> {
         // let storage = self.storage.as_ref();
         let mut boundary = Vec::new();
@@ -111,13 +202,15 @@ impl Eq for NextCell {}
 
 impl PartialOrd for NextCell {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        Reverse(self.cost).partial_cmp(&Reverse(other.cost))
+        Some(self.cmp(other))
     }
 }
 
 impl Ord for NextCell {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.partial_cmp(other).unwrap_or(Ordering::Equal)
+        Reverse(self.cost)
+            .partial_cmp(&Reverse(other.cost))
+            .unwrap_or(Ordering::Equal)
     }
 }
 
@@ -125,6 +218,13 @@ impl Ord for NextCell {
 pub(super) struct FastMarcher {
     next_cells: BinaryHeap<NextCell>,
     visited: Vec<f64>,
+    /// Optional seed index of each pixel, propagated along with the distance.
+    labels: Option<Vec<usize>>,
+    /// The number of pixels with nonzero `visited`.
+    num_visited: usize,
+    /// Whether each pixel has been accepted. Accepted pixels never change their distance.
+    frozen: Vec<bool>,
+    num_frozen: usize,
     dims: (usize, usize),
 }
 
@@ -144,16 +244,53 @@ impl FastMarcher {
         for NextCell { pos: (x, y), .. } in &next_cells {
             visited[x + y * dims.0] = 1.;
         }
+        let num_visited = visited.iter().filter(|v| **v != 0.).count();
         Self {
             next_cells,
             visited,
+            labels: None,
+            num_visited,
+            frozen: vec![false; dims.0 * dims.1],
+            num_frozen: 0,
             dims,
         }
     }
 
+    /// Create a marcher that also propagates the label of the pixel it came from, starting
+    /// from the given pixels and their labels.
+    pub(super) fn new_labeled(
+        next_cells: impl Iterator<Item = (GridPos, usize)>,
+        dims: (usize, usize),
+    ) -> Self {
+        let mut labels = vec![usize::MAX; dims.0 * dims.1];
+        let mut ret = Self::new(
+            next_cells.map(|(pos, label)| {
+                labels[pos.0 + pos.1 * dims.0] = label;
+                pos
+            }),
+            dims,
+        );
+        ret.labels = Some(labels);
+        ret
+    }
+
     /// Returns whether a pixel has changed; if not, there is no point iterating again
     fn evolve_single(&mut self, grid: &mut Grid) -> bool {
+        self.evolve_single_with(grid, &mut |_| ())
+    }
+
+    /// [`Self::evolve_single`] that calls `on_accept` with each cell accepted from the wavefront.
+    fn evolve_single_with(&mut self, grid: &mut Grid, on_accept: &mut dyn FnMut(GridPos)) -> bool {
         while let Some(next) = self.next_cells.pop() {
+            // A cell can be pushed more than once if its distance is lowered while in the
+            // narrow band, but only the first (smallest) one is accepted.
+            let frozen = &mut self.frozen[next.pos.0 + next.pos.1 * self.dims.0];
+            if *frozen {
+                continue;
+            }
+            *frozen = true;
+            self.num_frozen += 1;
+            on_accept(next.pos);
             let x = next.pos.0 as isize;
             let y = next.pos.1 as isize;
 
@@ -196,8 +333,17 @@ impl FastMarcher {
                 };
                 let (x, y) = (x as usize, y as usize);
                 let visited = self.visited[x + y * self.dims.0];
-                if (visited == 0. || next_cost < visited) && grid[(x, y)] != 0. {
+                if (visited == 0. || next_cost < visited)
+                    && !self.frozen[x + y * self.dims.0]
+                    && grid[(x, y)] != 0.
+                {
+                    if visited == 0. {
+                        self.num_visited += 1;
+                    }
                     self.visited[x + y * self.dims.0] = next_cost;
+                    if let Some(labels) = &mut self.labels {
+                        labels[x + y * self.dims.0] = labels[next.pos.0 + next.pos.1 * self.dims.0];
+                    }
                     let pos = (x, y);
                     let cost = next_cost;
                     grid[pos] = cost;
@@ -235,6 +381,21 @@ pub struct FMMCallbackData<'src> {
     ///
     /// You can examine "expanding wavefront" by iterating this iterator.
     pub next_pixels: &'src mut dyn Iterator<Item = GridPos>,
+    /// The position of the pixel accepted in this step, which is at the front of the wavefront.
+    pub accepted: GridPos,
+    /// The distance of the accepted pixel, i.e. how far the wavefront has reached.
+    pub distance: f64,
+    /// The number of pixels accepted so far.
+    pub accepted_count: usize,
+    /// The number of pixels in the narrow band, i.e. reached but not accepted yet.
+    pub narrow_band_count: usize,
+    /// The tentative distance of each pixel, or 0 if the wavefront has not reached it.
+    pub visited: &'src [f64],
+    /// Whether each pixel has been accepted (frozen). The distance of a frozen pixel never
+    /// changes afterwards.
+    ///
+    /// Pixels with nonzero `visited` that are not frozen are trial pixels in the narrow band.
+    pub frozen: &'src [bool],
 }
 
 impl FastMarcher {
@@ -243,10 +404,17 @@ impl FastMarcher {
         grid: &mut Grid,
         mut callback: impl FnMut(FMMCallbackData) -> bool,
     ) {
-        while self.evolve_single(grid) {
+        let mut accepted = (0, 0);
+        while self.evolve_single_with(grid, &mut |pos| accepted = pos) {
             if !callback(FMMCallbackData {
                 map: &grid.storage,
                 next_pixels: &mut self.next_cells.iter().map(|nc| nc.pos),
+                accepted,
+                distance: self.visited[accepted.0 + accepted.1 * self.dims.0],
+                accepted_count: self.num_frozen,
+                narrow_band_count: self.num_visited - self.num_frozen,
+                visited: &self.visited,
+                frozen: &self.frozen,
             }) {
                 return;
             }
@@ -260,6 +428,30 @@ impl FastMarcher {
             }
         }
     }
+
+    /// Evolve until `is_done` returns true for an accepted cell or the wavefront vanishes.
+    pub(super) fn evolve_until(
+        &mut self,
+        grid: &mut Grid,
+        mut is_done: impl FnMut(GridPos) -> bool,
+    ) {
+        let mut done = false;
+        while !done && self.evolve_single_with(grid, &mut |pos| done |= is_done(pos)) {}
+    }
+
+    /// Evolve until the cost of the nearest cell in the wavefront exceeds `max_cost`.
+    pub(super) fn evolve_bounded(&mut self, grid: &mut Grid, max_cost: f64) {
+        while self
+            .next_cells
+            .peek()
+            .map(|next| next.cost <= max_cost)
+            .unwrap_or(false)
+        {
+            if !self.evolve_single(grid) {
+                break;
+            }
+        }
+    }
 }
 
 #[cfg(test)]
@@ -296,4 +488,100 @@ mod test {
             approx_eq(*a, *b);
         }
     }
+
+    #[test]
+    fn test_edt_with() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        let luma: Vec<u8> = map.iter().map(|b| if *b { 200 } else { 30 }).collect();
+        assert_eq!(
+            edt_fmm_with(&luma, shape, |p| 128 < *p),
+            edt_fmm(&map, shape, false)
+        );
+    }
+
+    #[test]
+    fn test_edt_error() {
+        let shape = (60, 40);
+        let mut map = vec![true; shape.0 * shape.1];
+        for (x, y) in [(5, 7), (40, 3), (22, 30), (50, 35), (31, 18)] {
+            map[x + y * shape.0] = false;
+        }
+        let exact = crate::edt(&map, shape, false);
+        let fmm = edt_fmm(&map, shape, false);
+        for (i, (a, b)) in exact.iter().zip(fmm.iter()).enumerate() {
+            assert!(
+                (a - b).abs() < 1.,
+                "({}, {}): exact: {}, fmm: {}",
+                i % shape.0,
+                i / shape.0,
+                a,
+                b
+            );
+        }
+    }
+
+    #[test]
+    fn test_callback() {
+        let shape = (30, 20);
+        let map = sparse_map(shape);
+        let mut last_count = 0;
+        let mut max_distance = 0f64;
+        let ret = edt_fmm_cb(&map, shape, false, |data| {
+            let visited = data.visited.iter().filter(|v| **v != 0.).count();
+            let frozen = data.frozen.iter().filter(|f| **f).count();
+            assert_eq!(data.accepted_count, frozen);
+            assert_eq!(data.accepted_count + data.narrow_band_count, visited);
+            assert!(data.frozen[data.accepted.0 + data.accepted.1 * shape.0]);
+            assert!(last_count <= data.accepted_count);
+            last_count = data.accepted_count;
+            max_distance = max_distance.max(data.distance);
+            true
+        });
+        assert_eq!(ret, edt_fmm(&map, shape, false));
+        // The last pixels are accepted without changing any neighbors, hence without callbacks.
+        assert!(0. < max_distance && max_distance <= ret.iter().copied().fold(0., f64::max));
+    }
+
+    #[test]
+    fn test_frozen() {
+        let shape = (60, 40);
+        let mut map = vec![true; shape.0 * shape.1];
+        for (x, y) in [(5, 7), (40, 3), (22, 30), (50, 35), (31, 18)] {
+            map[x + y * shape.0] = false;
+        }
+        let mut frozen_visited: Vec<Option<f64>> = vec![None; map.len()];
+        edt_fmm_cb(&map, shape, false, |data| {
+            for (i, frozen) in data.frozen.iter().enumerate() {
+                if !frozen {
+                    assert!(frozen_visited[i].is_none());
+                } else if let Some(v) = frozen_visited[i] {
+                    assert_eq!(v, data.visited[i], "pixel {} changed after frozen", i);
+                } else {
+                    frozen_visited[i] = Some(data.visited[i]);
+                }
+            }
+            true
+        });
+        assert!(frozen_visited.iter().filter(|v| v.is_some()).count() > 0);
+    }
+
+    #[test]
+    fn test_bounded() {
+        let shape = (30, 20);
+        let map = sparse_map(shape);
+        let full = edt_fmm(&map, shape, false);
+        let bounded = edt_fmm_bounded(&map, shape, false, 4.5);
+        for (full, bounded) in full.iter().zip(&bounded) {
+            if *full <= 4.5 {
+                assert_eq!(full, bounded);
+            } else {
+                assert_eq!(*bounded, f64::INFINITY);
+            }
+        }
+        let negative = edt_fmm_bounded(&map, shape, false, -1.);
+        for (pixel, bounded) in map.iter().zip(&negative) {
+            assert_eq!(*bounded, if *pixel { f64::INFINITY } else { 0. });
+        }
+    }
 }
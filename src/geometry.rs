@@ -0,0 +1,120 @@
+//! Distance fields from vector geometry.
+//!
+//! Pixel `(x, y)` samples the point `(x as f64, y as f64)`, and the output has the same layout
+//! as [`crate::edt`]. Distances are computed exactly against every primitive, so the cost is
+//! proportional to the number of pixels times the number of primitives.
+
+/// A point in pixel coordinates.
+pub type Point = (f64, f64);
+
+/// Distance field to the nearest of the given points.
+///
+/// Every pixel gets infinity if `points` is empty.
+pub fn edt_points(points: &[Point], shape: (usize, usize)) -> Vec<f64> {
+    field(shape, |p| {
+        points
+            .iter()
+            .map(|q| dist_sq(p, *q))
+            .fold(f64::INFINITY, f64::min)
+            .sqrt()
+    })
+}
+
+/// Distance field to the nearest of the given line segments.
+///
+/// Every pixel gets infinity if `segments` is empty.
+pub fn edt_segments(segments: &[(Point, Point)], shape: (usize, usize)) -> Vec<f64> {
+    field(shape, |p| {
+        segments
+            .iter()
+            .map(|(a, b)| segment_dist_sq(p, *a, *b))
+            .fold(f64::INFINITY, f64::min)
+            .sqrt()
+    })
+}
+
+/// Signed distance field of a closed polygon given by its vertices.
+///
+/// The polygon is implicitly closed from the last vertex to the first. The distance is negative
+/// inside and positive outside, where inside is determined by the even-odd rule, so
+/// self-intersecting polygons are supported too.
+pub fn sdf_polygon(polygon: &[Point], shape: (usize, usize)) -> Vec<f64> {
+    let edges = || {
+        polygon
+            .iter()
+            .zip(polygon.iter().cycle().skip(1))
+            .map(|(a, b)| (*a, *b))
+    };
+    field(shape, |p| {
+        let dist = edges()
+            .map(|(a, b)| segment_dist_sq(p, a, b))
+            .fold(f64::INFINITY, f64::min)
+            .sqrt();
+        let crossings = edges()
+            .filter(|(a, b)| {
+                (a.1 <= p.1) != (b.1 <= p.1) && p.0 < a.0 + (p.1 - a.1) / (b.1 - a.1) * (b.0 - a.0)
+            })
+            .count();
+        if crossings % 2 == 1 {
+            -dist
+        } else {
+            dist
+        }
+    })
+}
+
+fn field(shape: (usize, usize), f: impl Fn(Point) -> f64) -> Vec<f64> {
+    (0..shape.0 * shape.1)
+        .map(|i| f(((i % shape.0) as f64, (i / shape.0) as f64)))
+        .collect()
+}
+
+fn dist_sq(a: Point, b: Point) -> f64 {
+    (a.0 - b.0).powf(2.) + (a.1 - b.1).powf(2.)
+}
+
+fn segment_dist_sq(p: Point, a: Point, b: Point) -> f64 {
+    let ab = (b.0 - a.0, b.1 - a.1);
+    let len_sq = ab.0 * ab.0 + ab.1 * ab.1;
+    if len_sq == 0. {
+        return dist_sq(p, a);
+    }
+    let t = (((p.0 - a.0) * ab.0 + (p.1 - a.1) * ab.1) / len_sq).clamp(0., 1.);
+    dist_sq(p, (a.0 + t * ab.0, a.1 + t * ab.1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_points() {
+        let dist = edt_points(&[(1.5, 0.), (4., 3.)], (5, 4));
+        assert_eq!(dist[0], 1.5);
+        assert_eq!(dist[4 + 3 * 5], 0.);
+        assert_eq!(dist[4], 2.5);
+        assert!(edt_points(&[], (2, 2)).iter().all(|d| d.is_infinite()));
+    }
+
+    #[test]
+    fn test_segments() {
+        let shape = (5, 4);
+        let dist = edt_segments(&[((1., 2.), (3., 2.))], shape);
+        assert_eq!(dist[2 * shape.0], 1.);
+        assert_eq!(dist[2], 2.);
+        assert_eq!(dist[2 + 2 * shape.0], 0.);
+        assert_eq!(dist[4 + 3 * shape.0], 2f64.sqrt());
+    }
+
+    #[test]
+    fn test_polygon() {
+        let shape = (7, 7);
+        let square = [(1., 1.), (5., 1.), (5., 5.), (1., 5.)];
+        let sdf = sdf_polygon(&square, shape);
+        assert_eq!(sdf[3 + 3 * shape.0], -2.);
+        assert_eq!(sdf[3 * shape.0], 1.);
+        assert_eq!(sdf[1 + 3 * shape.0], 0.);
+        assert_eq!(sdf[6 + 6 * shape.0], 2f64.sqrt());
+        assert_eq!(sdf[2 + 2 * shape.0], -1.);
+    }
+}
@@ -0,0 +1,96 @@
+use super::GridPos;
+
+/// Gradient of a distance field, such as the output of [`crate::edt`], by finite differences.
+///
+/// It uses central differences in smooth regions and one-sided differences at the border of
+/// the image. Where the forward and backward differences have opposite signs, the field has
+/// a kink (e.g. at the medial axis), where the central difference would cancel out to a
+/// meaningless small value, so the one-sided difference with the larger magnitude is used instead.
+pub fn gradient(field: &[f64], shape: (usize, usize)) -> Vec<(f64, f64)> {
+    let at = |x: usize, y: usize| field[x + y * shape.0];
+
+    let derivative = |backward: Option<f64>, forward: Option<f64>| match (backward, forward) {
+        (Some(b), Some(f)) => {
+            if 0. < b * f || b == 0. || f == 0. {
+                (b + f) / 2.
+            } else if f.abs() <= b.abs() {
+                b
+            } else {
+                f
+            }
+        }
+        (Some(b), None) => b,
+        (None, Some(f)) => f,
+        (None, None) => 0.,
+    };
+
+    (0..shape.0 * shape.1)
+        .map(|i| {
+            let (x, y) = (i % shape.0, i / shape.0);
+            let v = at(x, y);
+            let dx = derivative(
+                (0 < x).then(|| v - at(x - 1, y)),
+                (x + 1 < shape.0).then(|| at(x + 1, y) - v),
+            );
+            let dy = derivative(
+                (0 < y).then(|| v - at(x, y - 1)),
+                (y + 1 < shape.1).then(|| at(x, y + 1) - v),
+            );
+            (dx, dy)
+        })
+        .collect()
+}
+
+/// Exact unit normals from a feature transform given by [`crate::feature_transform`].
+///
+/// Each normal points from the pixel to its nearest obstacle pixel. Obstacle pixels themselves
+/// and pixels without a feature get `None`.
+pub fn feature_normals(
+    features: &[Option<GridPos>],
+    shape: (usize, usize),
+) -> Vec<Option<(f64, f64)>> {
+    features
+        .iter()
+        .enumerate()
+        .map(|(i, feature)| {
+            let (fx, fy) = (*feature)?;
+            let dx = fx as f64 - (i % shape.0) as f64;
+            let dy = fy as f64 - (i / shape.0) as f64;
+            let len = dx.hypot(dy);
+            (len != 0.).then(|| (dx / len, dy / len))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{edt, feature_transform};
+
+    #[test]
+    fn test_ramp() {
+        let shape = (4, 3);
+        let field: Vec<f64> = (0..12).map(|i| (i % shape.0) as f64 * 2.).collect();
+        assert!(gradient(&field, shape).iter().all(|g| *g == (2., 0.)));
+    }
+
+    #[test]
+    fn test_medial_axis() {
+        // A horizontal bar whose center row is the ridge of EDT.
+        let shape = (9, 5);
+        let dist = edt(&vec![true; shape.0 * shape.1], shape, false);
+        let grad = gradient(&dist, shape);
+        assert_eq!(grad[4 + shape.0], (0., 1.));
+        assert_eq!(grad[4 + 2 * shape.0].1.abs(), 1.);
+    }
+
+    #[test]
+    fn test_feature_normals() {
+        let shape = (6, 7);
+        let mut map = vec![true; shape.0 * shape.1];
+        map[2 + 2 * shape.0] = false;
+        let normals = feature_normals(&feature_transform(&map, shape, false), shape);
+        assert_eq!(normals[5 + 6 * shape.0], Some((-0.6, -0.8)));
+        assert_eq!(normals[2 + 2 * shape.0], None);
+    }
+}
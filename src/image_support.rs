@@ -0,0 +1,114 @@
+//! Integration with the [image](https://crates.io/crates/image) crate, enabled by the `image` feature.
+
+use super::{exact_edt::edt_sq_by, fast_marcher::edt_fmm_by, DistanceField, GridPos, StridedView};
+use image::{DynamicImage, GrayImage, ImageBuffer, Luma};
+
+/// The channel of an image to compute EDT of.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Channel {
+    /// Grayscale intensity.
+    Luma,
+    /// Opacity, e.g. for sprites with transparent background.
+    Alpha,
+}
+
+/// Options for [`edt_image`].
+#[derive(Clone, Copy, Debug)]
+pub struct ImageEdtOptions {
+    /// The channel to compute EDT of.
+    pub channel: Channel,
+    /// Pixels whose channel value is greater than this are the foreground.
+    pub threshold: u8,
+    /// Swap the foreground and obstacles, like the `invert` argument of [`crate::edt`].
+    pub invert: bool,
+    /// Use Fast Marching method instead of the exact EDT.
+    pub fast_marching: bool,
+}
+
+impl Default for ImageEdtOptions {
+    /// Nonzero luma pixels are the foreground, like [`crate::edt`] with an 8 bits grayscale image.
+    fn default() -> Self {
+        Self {
+            channel: Channel::Luma,
+            threshold: 0,
+            invert: false,
+            fast_marching: false,
+        }
+    }
+}
+
+/// Compute EDT of a channel of an image.
+pub fn edt_image(img: &DynamicImage, opts: ImageEdtOptions) -> ImageBuffer<Luma<f32>, Vec<f32>> {
+    let shape = (img.width() as usize, img.height() as usize);
+    let (channels, raw) = match opts.channel {
+        Channel::Luma => (1, img.to_luma8().into_raw()),
+        Channel::Alpha => (2, img.to_luma_alpha8().into_raw()),
+    };
+    let view = StridedView::channel(&raw, shape, channels, channels - 1);
+    let is_fg = |pos: GridPos| (opts.threshold < *view.get(pos)) != opts.invert;
+
+    let dist = if opts.fast_marching {
+        edt_fmm_by(shape, is_fg)
+    } else {
+        edt_sq_by(shape, is_fg, true)
+            .into_iter()
+            .map(f64::sqrt)
+            .collect()
+    };
+
+    ImageBuffer::from_vec(
+        img.width(),
+        img.height(),
+        dist.into_iter().map(|d| d as f32).collect(),
+    )
+    .unwrap()
+}
+
+impl DistanceField {
+    /// Convert to an 8 bits grayscale image normalized by the maximum value, e.g. for saving.
+    pub fn to_luma8(&self) -> GrayImage {
+        ImageBuffer::from_vec(self.width() as u32, self.height() as u32, self.to_u8()).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{edt, test_util::*};
+    use image::{Rgba, RgbaImage};
+
+    #[test]
+    fn test_edt_image() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        let luma = GrayImage::from_fn(shape.0 as u32, shape.1 as u32, |x, y| {
+            Luma([if map[x as usize + y as usize * shape.0] {
+                200
+            } else {
+                20
+            }])
+        });
+        let expected: Vec<f32> = edt(&map, shape, false).iter().map(|d| *d as f32).collect();
+        let opts = ImageEdtOptions {
+            threshold: 100,
+            ..ImageEdtOptions::default()
+        };
+        let res = edt_image(&DynamicImage::ImageLuma8(luma), opts);
+        assert_eq!(res.into_raw(), expected);
+
+        let rgba = RgbaImage::from_fn(shape.0 as u32, shape.1 as u32, |x, y| {
+            Rgba([
+                255,
+                255,
+                255,
+                map[x as usize + y as usize * shape.0] as u8 * 255,
+            ])
+        });
+        let opts = ImageEdtOptions {
+            channel: Channel::Alpha,
+            ..ImageEdtOptions::default()
+        };
+        let res = edt_image(&DynamicImage::ImageRgba8(rgba), opts);
+        assert_eq!(res.into_raw(), expected);
+    }
+}
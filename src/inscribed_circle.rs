@@ -0,0 +1,110 @@
+use super::{edt, BoolLike};
+
+/// A circle returned by [`max_inscribed_circle`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct InscribedCircle {
+    /// The center in pixel coordinates, refined to sub-pixel precision.
+    pub center: (f64, f64),
+    /// The radius, which is the distance to the nearest obstacle pixel center like [`crate::edt`].
+    pub radius: f64,
+}
+
+/// Find the largest circle that fits inside the foreground of a binary image.
+///
+/// The circle is centered at the maximum of [`crate::edt`], refined to sub-pixel precision by
+/// fitting a quadratic surface to the 3x3 neighborhood of the maximum.
+/// The arguments are interpreted the same as [`crate::edt`].
+/// Returns `None` if there is no foreground pixel.
+pub fn max_inscribed_circle<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+) -> Option<InscribedCircle> {
+    let dist = edt(map, shape, invert);
+    let (max_idx, max_val) = dist
+        .iter()
+        .copied()
+        .enumerate()
+        .fold((0, 0.), |acc, (i, d)| if acc.1 < d { (i, d) } else { acc });
+    if max_val == 0. {
+        return None;
+    }
+    let (x, y) = (max_idx % shape.0, max_idx / shape.0);
+
+    if x == 0 || shape.0 <= x + 1 || y == 0 || shape.1 <= y + 1 {
+        return Some(InscribedCircle {
+            center: (x as f64, y as f64),
+            radius: max_val,
+        });
+    }
+
+    let f = |dx: isize, dy: isize| {
+        dist[(x as isize + dx) as usize + (y as isize + dy) as usize * shape.0]
+    };
+
+    // Least squares fit of f = a + b x + c y + d x^2 + e x y + g y^2 on the 3x3 samples.
+    let mut b = 0.;
+    let mut c = 0.;
+    let mut d = 0.;
+    let mut g = 0.;
+    for i in -1..=1 {
+        b += (f(1, i) - f(-1, i)) / 6.;
+        c += (f(i, 1) - f(i, -1)) / 6.;
+        d += (f(1, i) - 2. * f(0, i) + f(-1, i)) / 6.;
+        g += (f(i, 1) - 2. * f(i, 0) + f(i, -1)) / 6.;
+    }
+    let e = (f(1, 1) - f(1, -1) - f(-1, 1) + f(-1, -1)) / 4.;
+
+    let det = 4. * d * g - e * e;
+    let (ox, oy) = if d < 0. && 0. < det {
+        ((-2. * g * b + e * c) / det, (-2. * d * c + e * b) / det)
+    } else {
+        // The surface is not a peak (e.g. a ridge), so refine each axis separately.
+        let axis = |grad: f64, curv: f64| if curv < 0. { -grad / (2. * curv) } else { 0. };
+        (axis(b, d), axis(c, g))
+    };
+    // The maximum pixel is the nearest sample to the true peak.
+    let (ox, oy) = (ox.clamp(-0.5, 0.5), oy.clamp(-0.5, 0.5));
+    let refined = max_val + b * ox + c * oy + d * ox * ox + e * ox * oy + g * oy * oy;
+
+    Some(InscribedCircle {
+        center: (x as f64 + ox, y as f64 + oy),
+        radius: refined.max(max_val),
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_disk() {
+        let shape = (41, 31);
+        let (cx, cy, r) = (20.5, 15.3, 10.);
+        let map: Vec<bool> = (0..shape.0 * shape.1)
+            .map(|i| {
+                let (x, y) = ((i % shape.0) as f64, (i / shape.0) as f64);
+                (x - cx).powf(2.) + (y - cy).powf(2.) < r * r
+            })
+            .collect();
+        let circle = max_inscribed_circle(&map, shape, false).unwrap();
+        eprintln!("{:?}", circle);
+        assert!((circle.center.0 - cx).abs() < 0.5);
+        assert!((circle.center.1 - cy).abs() < 0.5);
+        assert!((circle.radius - r).abs() < 1.);
+    }
+
+    #[test]
+    fn test_rectangle() {
+        // The maximum is a plateau along the x axis, so it should stay on the center row.
+        let map = vec![true; 12 * 7];
+        let circle = max_inscribed_circle(&map, (12, 7), false).unwrap();
+        assert_eq!(circle.center.1, 3.);
+        assert!((circle.radius - 4.).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_empty() {
+        assert_eq!(max_inscribed_circle(&[0u8; 9], (3, 3), false), None);
+    }
+}
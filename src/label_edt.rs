@@ -0,0 +1,105 @@
+use super::{exact_edt::feature_transform_by, GridPos};
+use std::{collections::HashMap, hash::Hash};
+
+/// The result of [`label_edt`].
+pub struct LabelEdt<L> {
+    /// The distance to the nearest pixel with a different label, or infinity if there is none.
+    pub distance: Vec<f64>,
+    /// The label of the nearest pixel with a different label, if any.
+    pub nearest_label: Vec<Option<L>>,
+}
+
+/// Label-aware EDT of a label image, such as region IDs produced by segmentation.
+///
+/// For each pixel, it computes the Euclidean distance to the nearest pixel of a *different* label
+/// and which label that is. It is built on the exact feature transform, applied once per label
+/// within the bounding box of the label (expanded by a pixel), so the cost depends on the sizes
+/// of the regions rather than the number of labels times the image size.
+pub fn label_edt<L: Copy + Eq + Hash>(labels: &[L], shape: (usize, usize)) -> LabelEdt<L> {
+    let mut bounds: HashMap<L, (GridPos, GridPos)> = HashMap::new();
+    for (i, label) in labels.iter().enumerate() {
+        let (x, y) = (i % shape.0, i / shape.0);
+        let bound = bounds.entry(*label).or_insert(((x, y), (x, y)));
+        bound.0 = (bound.0 .0.min(x), bound.0 .1.min(y));
+        bound.1 = (bound.1 .0.max(x), bound.1 .1.max(y));
+    }
+
+    let mut distance = vec![f64::INFINITY; labels.len()];
+    let mut nearest_label = vec![None; labels.len()];
+
+    for (label, (min, max)) in bounds {
+        // Any pixel outside of the bounding box has a different label, so the ring of pixels
+        // around it always contains a pixel closer than the ones beyond.
+        let x0 = min.0.saturating_sub(1);
+        let y0 = min.1.saturating_sub(1);
+        let x1 = (max.0 + 2).min(shape.0);
+        let y1 = (max.1 + 2).min(shape.1);
+        let local_shape = (x1 - x0, y1 - y0);
+        let ft = feature_transform_by(local_shape, |(x, y)| {
+            labels[x + x0 + (y + y0) * shape.0] == label
+        });
+
+        for y in min.1..=max.1 {
+            for x in min.0..=max.0 {
+                let i = x + y * shape.0;
+                if labels[i] != label {
+                    continue;
+                }
+                if let Some((fx, fy)) = ft[x - x0 + (y - y0) * local_shape.0] {
+                    let (fx, fy) = (fx + x0, fy + y0);
+                    distance[i] =
+                        ((fx as f64 - x as f64).powf(2.) + (fy as f64 - y as f64).powf(2.)).sqrt();
+                    nearest_label[i] = Some(labels[fx + fy * shape.0]);
+                }
+            }
+        }
+    }
+
+    LabelEdt {
+        distance,
+        nearest_label,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_label_edt() {
+        let labels: Vec<u16> = [
+            [1, 1, 1, 2, 2],
+            [1, 1, 1, 2, 2],
+            [1, 1, 3, 3, 2],
+            [1, 1, 3, 3, 2],
+        ]
+        .iter()
+        .flatten()
+        .copied()
+        .collect();
+        let shape = (5, 4);
+        let res = label_edt(&labels, shape);
+        for (i, label) in labels.iter().enumerate() {
+            let (x, y) = (i % shape.0, i / shape.0);
+            let brute = (0..labels.len())
+                .filter(|j| labels[*j] != *label)
+                .map(|j| {
+                    let (jx, jy) = ((j % shape.0) as f64, (j / shape.0) as f64);
+                    ((jx - x as f64).powf(2.) + (jy - y as f64).powf(2.)).sqrt()
+                })
+                .reduce(f64::min)
+                .unwrap();
+            assert_eq!(res.distance[i], brute);
+        }
+        assert_eq!(res.nearest_label[2], Some(2));
+        assert_eq!(res.nearest_label[3 * 5], Some(3));
+        assert_eq!(res.nearest_label[3 * 5 + 3], Some(2));
+    }
+
+    #[test]
+    fn test_single_label() {
+        let res = label_edt(&[7u32; 6], (3, 2));
+        assert!(res.distance.iter().all(|d| *d == f64::INFINITY));
+        assert!(res.nearest_label.iter().all(Option::is_none));
+    }
+}
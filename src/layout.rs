@@ -0,0 +1,118 @@
+use super::{exact_edt::edt_sq_by, fast_marcher::edt_fmm_by, BoolLike, GridPos};
+
+/// Memory layout of a 2D image in a flat slice.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Layout {
+    /// Pixels in a row are contiguous, i.e. `x + y * width`. It is what [`crate::edt`] assumes.
+    #[default]
+    RowMajor,
+    /// Pixels in a column are contiguous, i.e. `y + x * height`, like MATLAB, Fortran or
+    /// NumPy arrays with `order='F'`.
+    ColumnMajor,
+}
+
+impl Layout {
+    /// The index of the pixel at `pos` in a slice of this layout.
+    pub fn index(self, pos: GridPos, shape: (usize, usize)) -> usize {
+        match self {
+            Self::RowMajor => pos.0 + pos.1 * shape.0,
+            Self::ColumnMajor => pos.1 + pos.0 * shape.1,
+        }
+    }
+
+    /// A column-major image is a row-major image of the transposed shape.
+    fn row_major_shape(self, shape: (usize, usize)) -> (usize, usize) {
+        match self {
+            Self::RowMajor => shape,
+            Self::ColumnMajor => (shape.1, shape.0),
+        }
+    }
+}
+
+/// EDT of an image in the given memory layout.
+///
+/// `shape` is `(width, height)` regardless of the layout, and the returned vec has the same
+/// layout as the input. Since EDT commutes with transposition, a column-major image is
+/// transformed as the transposed row-major image without copying.
+/// See [`crate::edt`] for the other arguments.
+pub fn edt_layout<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    layout: Layout,
+) -> Vec<f64> {
+    let mut ret = edt_sq_layout(map, shape, invert, layout);
+    for pixel in &mut ret {
+        *pixel = pixel.sqrt();
+    }
+    ret
+}
+
+/// Squared EDT of an image in the given memory layout.
+///
+/// The interface is equivalent to [`edt_layout`], but it returns squared EDT.
+pub fn edt_sq_layout<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    layout: Layout,
+) -> Vec<f64> {
+    let shape = layout.row_major_shape(shape);
+    edt_sq_by(
+        shape,
+        |(x, y)| map[x + y * shape.0].as_bool() != invert,
+        true,
+    )
+}
+
+/// EDT using Fast Marching method of an image in the given memory layout.
+///
+/// See [`edt_layout`] and [`crate::edt_fmm`] for details.
+pub fn edt_fmm_layout<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    layout: Layout,
+) -> Vec<f64> {
+    let shape = layout.row_major_shape(shape);
+    edt_fmm_by(shape, |(x, y)| map[x + y * shape.0].as_bool() != invert)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{edt, edt_fmm, test_util::*};
+
+    fn to_column_major<T: Copy>(v: &[T], shape: (usize, usize)) -> Vec<T> {
+        (0..v.len())
+            .map(|i| v[Layout::RowMajor.index((i / shape.1, i % shape.1), shape)])
+            .collect()
+    }
+
+    #[test]
+    fn test_column_major() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        let col_map = to_column_major(&map, shape);
+        assert_eq!(
+            col_map[Layout::ColumnMajor.index((4, 1), shape)],
+            map[Layout::RowMajor.index((4, 1), shape)]
+        );
+
+        let res = edt_layout(&col_map, shape, false, Layout::ColumnMajor);
+        assert_eq!(res, to_column_major(&edt(&map, shape, false), shape));
+
+        let res = edt_fmm_layout(&col_map, shape, false, Layout::ColumnMajor);
+        for (a, b) in res
+            .iter()
+            .zip(to_column_major(&edt_fmm(&map, shape, false), shape))
+        {
+            assert!((a - b).abs() < 1e-9, "a: {}, b: {}", a, b);
+        }
+
+        assert_eq!(
+            edt_layout(&map, shape, false, Layout::RowMajor),
+            edt(&map, shape, false)
+        );
+    }
+}
@@ -45,7 +45,7 @@
 //! which can be run by
 //!
 //! ```bash
-//! cargo r --release --example edt -- Rust_logo.png -d
+//! cargo r --release --features image --example edt -- Rust_logo.png -d
 //! ```
 //!
 //! ![Rust-logo-edt](https://raw.githubusercontent.com/msakuta/rust-edt/master/Rust_logo_diff.png)
@@ -91,6 +91,9 @@
 //! let edt_image = edt(&vec, (dims.0 as usize, dims.1 as usize), true);
 //! ```
 //!
+//! The outside of the image is regarded as an obstacle, so the pixels on the edges get distance 1
+//! even if there is no obstacle in the image.
+//!
 //! Save to a file if you want.
 //! The code below normalizes the value with maximum value to 8 bits grayscale image.
 //!
@@ -115,6 +118,15 @@
 //!
 //! See [examples](https://github.com/msakuta/rust-edt/tree/master/examples) folder for more.
 //!
+//! ## Cargo features
+//!
+//! The default build has no dependencies. You can opt in integrations with other crates:
+//!
+//! * `image`: `image_support::edt_image` computes EDT of a channel (luma or alpha) of a
+//!   `DynamicImage` directly, and `DistanceField::to_luma8` converts the result back to an image.
+//! * `ndarray`: `ndarray_support` module provides EDT of `ArrayView2` and `ArrayView3` of any
+//!   strides and memory order.
+//!
 //! ## Literature
 //!
 //! ### 2D Euclidean Distance Transform Algorithms: A Comparative Survey
@@ -137,9 +149,31 @@
 //!
 //! [Dijkstra and Fast Marching Algorithms (tutorial in Matlab)](https://www.numerical-tours.com/matlab/fastmarching_0_implementing/)
 
+mod chamfer;
+mod contour;
+mod distance_field;
+mod dynamic_edt;
 mod exact_edt;
 mod fast_marcher;
+pub mod geometry;
+mod gradient;
+#[cfg(feature = "image")]
+pub mod image_support;
+mod inscribed_circle;
+mod label_edt;
+mod layout;
+mod local_thickness;
+mod medial_axis;
+mod metrics;
+pub mod morphology;
+#[cfg(feature = "ndarray")]
+pub mod ndarray_support;
 mod primitive_impl;
+mod roi;
+mod streaming;
+mod strided;
+mod tiled;
+mod voronoi;
 
 /// A trait for types that can be interpreted as a bool.
 ///
@@ -152,8 +186,29 @@ pub trait BoolLike {
     fn as_bool(&self) -> bool;
 }
 
-pub use exact_edt::{edt, edt_sq};
-pub use fast_marcher::{edt_fmm, edt_fmm_cb, FMMCallbackData, GridPos};
+pub use chamfer::{chamfer_match, ChamferMatch, ChamferOptions, ChamferPlacement};
+pub use contour::{contours, Contour};
+pub use distance_field::{edt_field, edt_fmm_field, DistanceField, Interpolation, OutOfBounds};
+pub use dynamic_edt::DynamicEdt;
+pub use exact_edt::{
+    edt, edt_bounded, edt_cb, edt_sq, edt_sq_bounded, edt_sq_cb, edt_sq_with, edt_with,
+    feature_transform, EdtCallbackData, EdtPass,
+};
+pub use fast_marcher::{
+    edt_fmm, edt_fmm_bounded, edt_fmm_cb, edt_fmm_with, FMMCallbackData, GridPos,
+};
+pub use gradient::{feature_normals, gradient};
+pub use inscribed_circle::{max_inscribed_circle, InscribedCircle};
+pub use label_edt::{label_edt, LabelEdt};
+pub use layout::{edt_fmm_layout, edt_layout, edt_sq_layout, Layout};
+pub use local_thickness::{local_thickness, LocalThickness};
+pub use medial_axis::{medial_axis, MedialAxis, MedialAxisPruning};
+pub use metrics::{surface_distance, SurfaceDistance};
+pub use roi::{edt_fmm_roi, edt_roi, edt_sq_roi, Roi};
+pub use streaming::StreamingEdt;
+pub use strided::{edt_fmm_strided, edt_sq_strided, edt_strided, StridedView};
+pub use tiled::{edt_sq_tiled, edt_tiled, BandReader, BandWriter};
+pub use voronoi::{voronoi_fmm, voronoi_fmm_image, voronoi_labels, voronoi_labels_image, Voronoi};
 
 #[cfg(test)]
 mod test_util;
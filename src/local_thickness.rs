@@ -0,0 +1,114 @@
+use super::{edt, medial_axis, BoolLike, MedialAxisPruning};
+
+/// The result of [`local_thickness`].
+pub struct LocalThickness {
+    /// The local thickness of each foreground pixel, or zero for background pixels.
+    pub thickness: Vec<f64>,
+}
+
+impl LocalThickness {
+    /// Count foreground pixels into bins of thickness `[i * bin_width, (i + 1) * bin_width)`.
+    ///
+    /// The returned vec is long enough to contain the thickest pixel.
+    pub fn histogram(&self, bin_width: f64) -> Vec<usize> {
+        let mut hist = vec![];
+        for t in self.thickness.iter().filter(|t| **t != 0.) {
+            let bin = (t / bin_width) as usize;
+            if hist.len() <= bin {
+                hist.resize(bin + 1, 0);
+            }
+            hist[bin] += 1;
+        }
+        hist
+    }
+
+    /// The mean thickness of foreground pixels, or `None` if there are none.
+    pub fn mean(&self) -> Option<f64> {
+        let (sum, count) = self
+            .thickness
+            .iter()
+            .filter(|t| **t != 0.)
+            .fold((0., 0), |(sum, count), t| (sum + t, count + 1));
+        if count == 0 {
+            None
+        } else {
+            Some(sum / count as f64)
+        }
+    }
+}
+
+/// Compute local thickness of the foreground of a binary image.
+///
+/// The local thickness of a pixel is the diameter of the largest disk that fits inside the
+/// foreground and contains the pixel. The disks are centered at the ridge of [`crate::edt`]
+/// given by [`crate::medial_axis`], and their radii are the EDT values, i.e. the distances to
+/// the nearest obstacle pixel center. The arguments are interpreted the same as [`crate::edt`].
+pub fn local_thickness<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+) -> LocalThickness {
+    let dist = edt(map, shape, invert);
+    let axis = medial_axis(map, shape, invert, MedialAxisPruning::default());
+
+    let mut centers: Vec<_> = (0..dist.len()).filter(|i| axis.mask[*i]).collect();
+    centers.sort_by(|a, b| axis.radius[*b].total_cmp(&axis.radius[*a]));
+
+    // Paint disks from the largest, so that the first disk covering a pixel is the largest one.
+    let mut thickness = vec![0.; dist.len()];
+    for center in centers {
+        let r = axis.radius[center];
+        let (cx, cy) = ((center % shape.0) as isize, (center / shape.0) as isize);
+        let extent = r.ceil() as isize;
+        for y in (cy - extent).max(0)..(cy + extent + 1).min(shape.1 as isize) {
+            for x in (cx - extent).max(0)..(cx + extent + 1).min(shape.0 as isize) {
+                let i = x as usize + y as usize * shape.0;
+                if thickness[i] == 0.
+                    && dist[i] != 0.
+                    && (((x - cx).pow(2) + (y - cy).pow(2)) as f64) < r.powf(2.)
+                {
+                    thickness[i] = 2. * r;
+                }
+            }
+        }
+    }
+
+    // A pixel is at least as thick as the disk centered at itself.
+    for (t, d) in thickness.iter_mut().zip(&dist) {
+        *t = t.max(2. * d);
+    }
+
+    LocalThickness { thickness }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_local_thickness() {
+        // A thin bar and a thick bar, separated by background.
+        let shape = (12, 7);
+        let map: Vec<bool> = (0..shape.0 * shape.1)
+            .map(|i| {
+                let x = i % shape.0;
+                x != 3 && x != 11
+            })
+            .collect();
+        let lt = local_thickness(&map, shape, false);
+        for y in 0..shape.1 {
+            assert_eq!(lt.thickness[1 + y * shape.0], 4.);
+            assert_eq!(lt.thickness[3 + y * shape.0], 0.);
+        }
+        assert_eq!(lt.thickness[7 + 3 * shape.0], 8.);
+        assert_eq!(lt.thickness[4 + 3 * shape.0], 8.);
+
+        let hist = lt.histogram(1.);
+        assert_eq!(
+            hist.iter().sum::<usize>(),
+            map.iter().filter(|b| **b).count()
+        );
+        assert_eq!(hist[4], 3 * shape.1);
+        assert!(0. < lt.mean().unwrap());
+    }
+}
@@ -0,0 +1,168 @@
+use super::{exact_edt::feature_transform_by, BoolLike, GridPos};
+
+/// Controls to prune spurious branches of the medial axis caused by boundary noise.
+#[derive(Clone, Copy, Debug)]
+pub struct MedialAxisPruning {
+    /// Minimum distance between the nearest boundary points of adjacent pixels for them to
+    /// form a ridge. Small bumps on the boundary produce branches with closely spaced
+    /// boundary points, so increasing this value removes them.
+    pub min_separation: f64,
+    /// Minimum radius (distance to the boundary) of a pixel to be kept in the skeleton.
+    pub min_radius: f64,
+}
+
+impl Default for MedialAxisPruning {
+    fn default() -> Self {
+        Self {
+            min_separation: 1.,
+            min_radius: 0.,
+        }
+    }
+}
+
+/// The result of [`medial_axis`].
+pub struct MedialAxis {
+    /// Whether each pixel belongs to the skeleton.
+    pub mask: Vec<bool>,
+    /// The radius of the maximal disk centered at each skeleton pixel, or zero for the others.
+    pub radius: Vec<f64>,
+}
+
+/// Extract the medial axis (skeleton) of the foreground from the feature transform.
+///
+/// A pair of adjacent foreground pixels whose nearest obstacle pixels are far apart straddles
+/// a ridge of the distance field, and the one closer to the ridge is marked as the skeleton
+/// (the integer medial axis of Hesselink and Roerdink).
+/// The outside of the image is regarded as an obstacle like [`crate::edt`], so the radius
+/// agrees with the EDT of the same image.
+pub fn medial_axis<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    pruning: MedialAxisPruning,
+) -> MedialAxis {
+    let is_fg = |(x, y): GridPos| map[x + y * shape.0].as_bool() != invert;
+
+    // Pad the image with a frame of obstacles so that the border is regarded as an obstacle.
+    let padded_shape = (shape.0 + 2, shape.1 + 2);
+    let padded_ft = feature_transform_by(padded_shape, |(x, y)| {
+        0 < x && x <= shape.0 && 0 < y && y <= shape.1 && is_fg((x - 1, y - 1))
+    });
+    let feature = |(x, y): GridPos| {
+        let (fx, fy) = padded_ft[x + 1 + (y + 1) * padded_shape.0]
+            .expect("The padding should always have a feature");
+        (fx as f64 - 1., fy as f64 - 1.)
+    };
+
+    let mut mask = vec![false; shape.0 * shape.1];
+    let min_separation_sq = pruning.min_separation.powf(2.);
+
+    let mut check_pair = |p: GridPos, q: GridPos| {
+        if !is_fg(p) || !is_fg(q) {
+            return;
+        }
+        let (fp, fq) = (feature(p), feature(q));
+        if (fp.0 - fq.0).powf(2.) + (fp.1 - fq.1).powf(2.) <= min_separation_sq {
+            return;
+        }
+        let (sx, sy) = ((p.0 + q.0) as f64, (p.1 + q.1) as f64);
+        let crit = (fq.0 - fp.0) * (fq.0 + fp.0 - sx) + (fq.1 - fp.1) * (fq.1 + fp.1 - sy);
+        if crit <= 0. {
+            mask[p.0 + p.1 * shape.0] = true;
+        }
+        if 0. <= crit {
+            mask[q.0 + q.1 * shape.0] = true;
+        }
+    };
+
+    for y in 0..shape.1 {
+        for x in 0..shape.0 {
+            if x + 1 < shape.0 {
+                check_pair((x, y), (x + 1, y));
+            }
+            if y + 1 < shape.1 {
+                check_pair((x, y), (x, y + 1));
+            }
+        }
+    }
+
+    let mut radius = vec![0.; shape.0 * shape.1];
+    for (i, on_axis) in mask.iter_mut().enumerate() {
+        if !*on_axis {
+            continue;
+        }
+        let (x, y) = ((i % shape.0) as f64, (i / shape.0) as f64);
+        let f = feature((i % shape.0, i / shape.0));
+        let r = ((f.0 - x).powf(2.) + (f.1 - y).powf(2.)).sqrt();
+        if r < pruning.min_radius {
+            *on_axis = false;
+        } else {
+            radius[i] = r;
+        }
+    }
+
+    MedialAxis { mask, radius }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::edt;
+
+    fn print_mask(mask: &[bool], shape: (usize, usize)) {
+        for row in mask.chunks(shape.0) {
+            eprintln!(
+                "{}",
+                row.iter()
+                    .map(|b| if *b { '1' } else { '0' })
+                    .collect::<String>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_rectangle() {
+        let shape = (11, 5);
+        let map = vec![true; shape.0 * shape.1];
+        let axis = medial_axis(&map, shape, false, MedialAxisPruning::default());
+        print_mask(&axis.mask, shape);
+        let dist = edt(&map, shape, false);
+        for x in 3..8 {
+            let i = x + 2 * shape.0;
+            assert!(axis.mask[i]);
+            assert_eq!(axis.radius[i], 3.);
+            assert_eq!(axis.radius[i], dist[i]);
+        }
+        for x in 3..8 {
+            assert!(!axis.mask[x + shape.0]);
+            assert!(!axis.mask[x + 3 * shape.0]);
+        }
+    }
+
+    #[test]
+    fn test_pruning() {
+        // A rectangle with a one pixel dent in the top boundary.
+        let shape = (15, 9);
+        let mut map = vec![true; shape.0 * shape.1];
+        map[7] = false;
+        let noisy = medial_axis(&map, shape, false, MedialAxisPruning::default());
+        print_mask(&noisy.mask, shape);
+        assert!(noisy.mask[5 + shape.0 * 2]);
+
+        let pruned = medial_axis(
+            &map,
+            shape,
+            false,
+            MedialAxisPruning {
+                min_separation: 5.,
+                min_radius: 3.5,
+            },
+        );
+        print_mask(&pruned.mask, shape);
+        assert!(!pruned.mask[5 + shape.0 * 2]);
+        for x in 4..11 {
+            assert!(pruned.mask[x + shape.0 * 4]);
+        }
+        assert!(pruned.radius.iter().all(|r| *r == 0. || 3.5 <= *r));
+    }
+}
@@ -0,0 +1,176 @@
+use super::{exact_edt::edt_sq_by, BoolLike, GridPos};
+
+/// Surface distances between two masks, see [`surface_distance`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct SurfaceDistance {
+    /// The Hausdorff distance, i.e. the maximum distance from a boundary pixel of either mask to
+    /// the nearest boundary pixel of the other.
+    pub hausdorff: f64,
+    /// The maximum of the 95th percentiles of the distances in each direction, which is less
+    /// sensitive to outliers than the Hausdorff distance.
+    pub hausdorff95: f64,
+    /// The average symmetric surface distance (a.k.a. chamfer distance), i.e. the mean of the
+    /// average distances in each direction.
+    pub average_symmetric: f64,
+}
+
+/// Compute surface distances between two masks, e.g. a predicted and a ground truth segmentation.
+///
+/// Nonzero pixels are regarded as the foreground. The boundary of a mask is the set of
+/// foreground pixels having a background pixel or the outside of the image as a 4-neighbor.
+/// The distances from the boundary pixels of one mask are looked up in the EDT of the boundary of
+/// the other, avoiding pairwise comparisons. Returns `None` if either mask is empty.
+pub fn surface_distance<T: BoolLike>(
+    a: &[T],
+    b: &[T],
+    shape: (usize, usize),
+) -> Option<SurfaceDistance> {
+    let boundary_a = boundary(a, shape);
+    let boundary_b = boundary(b, shape);
+
+    let mut dist_ab = directed_distances(&boundary_a, &boundary_b, shape);
+    let mut dist_ba = directed_distances(&boundary_b, &boundary_a, shape);
+    if dist_ab.is_empty() || dist_ba.is_empty() {
+        return None;
+    }
+
+    let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+    let average_symmetric = (mean(&dist_ab) + mean(&dist_ba)) / 2.;
+
+    // Percentiles are taken in each direction, so that an error only on one side is not
+    // diluted by the other.
+    dist_ab.sort_by(f64::total_cmp);
+    dist_ba.sort_by(f64::total_cmp);
+
+    Some(SurfaceDistance {
+        hausdorff: dist_ab.last().unwrap().max(*dist_ba.last().unwrap()),
+        hausdorff95: percentile(&dist_ab, 0.95).max(percentile(&dist_ba, 0.95)),
+        average_symmetric,
+    })
+}
+
+fn boundary<T: BoolLike>(map: &[T], shape: (usize, usize)) -> Vec<bool> {
+    let is_fg = |(x, y): GridPos| map[x + y * shape.0].as_bool();
+    (0..shape.0 * shape.1)
+        .map(|i| {
+            let (x, y) = (i % shape.0, i / shape.0);
+            is_fg((x, y))
+                && (x == 0
+                    || y == 0
+                    || shape.0 <= x + 1
+                    || shape.1 <= y + 1
+                    || !is_fg((x - 1, y))
+                    || !is_fg((x, y - 1))
+                    || !is_fg((x + 1, y))
+                    || !is_fg((x, y + 1)))
+        })
+        .collect()
+}
+
+/// Distances from each boundary pixel in `from` to the nearest boundary pixel in `to`.
+fn directed_distances(from: &[bool], to: &[bool], shape: (usize, usize)) -> Vec<f64> {
+    if !to.iter().any(|b| *b) {
+        return vec![];
+    }
+    let dist_sq = edt_sq_by(shape, |(x, y)| !to[x + y * shape.0], false);
+    from.iter()
+        .zip(dist_sq)
+        .filter(|(b, _)| **b)
+        .map(|(_, d)| d.sqrt())
+        .collect()
+}
+
+/// Percentile of a sorted slice with linear interpolation between the closest ranks.
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p * (sorted.len() - 1) as f64;
+    let lo = rank.floor() as usize;
+    let hi = rank.ceil() as usize;
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn square(shape: (usize, usize), x0: usize, y0: usize, size: usize) -> Vec<bool> {
+        (0..shape.0 * shape.1)
+            .map(|i| {
+                let (x, y) = (i % shape.0, i / shape.0);
+                (x0..x0 + size).contains(&x) && (y0..y0 + size).contains(&y)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_surface_distance() {
+        let shape = (12, 10);
+        let a = square(shape, 1, 1, 5);
+        let b = square(shape, 4, 2, 6);
+
+        // Brute force over pairs of boundary pixels
+        let directed = |from: &[bool], to: &[bool]| -> Vec<f64> {
+            let pixels = |m: &[bool]| -> Vec<(f64, f64)> {
+                (0..m.len())
+                    .filter(|i| m[*i])
+                    .map(|i| ((i % shape.0) as f64, (i / shape.0) as f64))
+                    .collect()
+            };
+            let to = pixels(to);
+            pixels(from)
+                .iter()
+                .map(|p| {
+                    to.iter()
+                        .map(|q| ((p.0 - q.0).powf(2.) + (p.1 - q.1).powf(2.)).sqrt())
+                        .fold(f64::INFINITY, f64::min)
+                })
+                .collect()
+        };
+        let (ba, bb) = (boundary(&a, shape), boundary(&b, shape));
+        let (ab, ba) = (directed(&ba, &bb), directed(&bb, &ba));
+        let max = ab.iter().chain(&ba).copied().fold(0., f64::max);
+        let mean = |v: &[f64]| v.iter().sum::<f64>() / v.len() as f64;
+
+        let percentile95 = |v: &[f64]| {
+            let mut v = v.to_vec();
+            v.sort_by(f64::total_cmp);
+            percentile(&v, 0.95)
+        };
+
+        let res = surface_distance(&a, &b, shape).unwrap();
+        assert_eq!(res.hausdorff, max);
+        assert!((res.average_symmetric - (mean(&ab) + mean(&ba)) / 2.).abs() < 1e-12);
+        assert_eq!(res.hausdorff95, percentile95(&ab).max(percentile95(&ba)));
+    }
+
+    #[test]
+    fn test_one_sided_error() {
+        // A false positive blob far from the square only affects the distances from b to a,
+        // which should not be diluted by the distances from a to b.
+        let shape = (40, 30);
+        let a = square(shape, 2, 2, 20);
+        let mut b = a.clone();
+        for (i, pixel) in square(shape, 33, 20, 3).iter().enumerate() {
+            b[i] |= pixel;
+        }
+        let res = surface_distance(&a, &b, shape).unwrap();
+        assert!(10. < res.hausdorff95, "{:?}", res);
+        assert!(res.hausdorff95 <= res.hausdorff);
+    }
+
+    #[test]
+    fn test_identical() {
+        let shape = (8, 8);
+        let a = square(shape, 2, 2, 4);
+        let res = surface_distance(&a, &a, shape).unwrap();
+        assert_eq!(res.hausdorff, 0.);
+        assert_eq!(res.hausdorff95, 0.);
+        assert_eq!(res.average_symmetric, 0.);
+        assert_eq!(surface_distance(&a, &[false; 64], shape), None);
+    }
+
+    #[test]
+    fn test_percentile() {
+        assert_eq!(percentile(&[0., 1., 2., 3., 4.], 0.5), 2.);
+        assert_eq!(percentile(&[0., 10.], 0.95), 9.5);
+    }
+}
@@ -0,0 +1,80 @@
+//! Euclidean morphology with disk shaped structuring elements.
+//!
+//! Each operation is computed from a squared EDT of the mask or its complement,
+//! so the cost does not depend on the radius.
+//! Unlike [`crate::edt`], the outside of the image is regarded as neither foreground nor
+//! background, so the border of the image does not erode or dilate the mask.
+
+use super::{exact_edt::edt_sq_by, BoolLike};
+
+/// Erode the foreground (nonzero pixels) with a disk of given radius.
+///
+/// A pixel stays foreground if no background pixel lies within `radius` from it.
+pub fn erode<T: BoolLike>(map: &[T], shape: (usize, usize), radius: f64) -> Vec<bool> {
+    let dist_sq = edt_sq_by(shape, |(x, y)| map[x + y * shape.0].as_bool(), false);
+    dist_sq.iter().map(|d| radius.powf(2.) < *d).collect()
+}
+
+/// Dilate the foreground (nonzero pixels) with a disk of given radius.
+///
+/// A pixel becomes foreground if a foreground pixel lies within `radius` from it.
+pub fn dilate<T: BoolLike>(map: &[T], shape: (usize, usize), radius: f64) -> Vec<bool> {
+    let dist_sq = edt_sq_by(shape, |(x, y)| !map[x + y * shape.0].as_bool(), false);
+    dist_sq.iter().map(|d| *d <= radius.powf(2.)).collect()
+}
+
+/// Morphological opening, i.e. [`erode`] followed by [`dilate`].
+///
+/// It removes foreground features smaller than the disk.
+pub fn open<T: BoolLike>(map: &[T], shape: (usize, usize), radius: f64) -> Vec<bool> {
+    dilate(&erode(map, shape, radius), shape, radius)
+}
+
+/// Morphological closing, i.e. [`dilate`] followed by [`erode`].
+///
+/// It fills background features (holes and gaps) smaller than the disk.
+pub fn close<T: BoolLike>(map: &[T], shape: (usize, usize), radius: f64) -> Vec<bool> {
+    erode(&dilate(map, shape, radius), shape, radius)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn parse_mask(s: &[&str]) -> Vec<bool> {
+        s.iter().flat_map(|s| s.chars().map(|c| c == '1')).collect()
+    }
+
+    #[test]
+    fn test_erode_dilate() {
+        let shape = (7, 7);
+        let map = parse_mask(&[
+            "0000000", //
+            "0111110", "0111110", "0111110", "0111110", "0111110", "0000000",
+        ]);
+        let eroded = parse_mask(&[
+            "0000000", //
+            "0000000", "0011100", "0011100", "0011100", "0000000", "0000000",
+        ]);
+        assert_eq!(erode(&map, shape, 1.), eroded);
+        assert_eq!(dilate(&eroded, shape, 1.5), map);
+        // The corners of the square are farther than the radius.
+        let dilated = dilate(&eroded, shape, 1.);
+        assert!(!dilated[1 + shape.0]);
+        assert!(dilated[3 + shape.0]);
+    }
+
+    #[test]
+    fn test_open_close() {
+        let shape = (8, 5);
+        let map = parse_mask(&[
+            "10000000", //
+            "00001110", "00001010", "00001110", "00000000",
+        ]);
+        assert!(open(&map, shape, 1.).iter().all(|b| !*b));
+        let closed = close(&map, shape, 1.);
+        assert!(closed[5 + 2 * shape.0]);
+        assert!(closed[0]);
+        assert!(!closed[2 + 2 * shape.0]);
+    }
+}
@@ -0,0 +1,140 @@
+//! Integration with the [ndarray](https://crates.io/crates/ndarray) crate, enabled by the `ndarray` feature.
+//!
+//! The functions accept array views of any strides and memory order, so slices, transposed or
+//! column-major (Fortran order) arrays can be transformed without copying them.
+//! A column-major input produces a column-major output.
+
+use super::{exact_edt::edt_sq_by, fast_marcher::edt_fmm_by, BoolLike};
+use ndarray::{Array2, Array3, ArrayView2, ArrayView3, Axis};
+
+/// EDT of a 2D array, indexed by `[[y, x]]`.
+///
+/// See [`crate::edt`] for details.
+pub fn edt_array<T: BoolLike>(map: ArrayView2<T>, invert: bool) -> Array2<f64> {
+    edt_sq_array(map, invert).mapv_into(f64::sqrt)
+}
+
+/// Squared EDT of a 2D array, indexed by `[[y, x]]`.
+///
+/// See [`crate::edt_sq`] for details.
+pub fn edt_sq_array<T: BoolLike>(map: ArrayView2<T>, invert: bool) -> Array2<f64> {
+    with_layout(map, |view| {
+        edt_sq_by(
+            (view.ncols(), view.nrows()),
+            |(x, y)| view[[y, x]].as_bool() != invert,
+            true,
+        )
+    })
+}
+
+/// EDT using Fast Marching method of a 2D array, indexed by `[[y, x]]`.
+///
+/// See [`crate::edt_fmm`] for details.
+pub fn edt_fmm_array<T: BoolLike>(map: ArrayView2<T>, invert: bool) -> Array2<f64> {
+    with_layout(map, |view| {
+        edt_fmm_by((view.ncols(), view.nrows()), |(x, y)| {
+            view[[y, x]].as_bool() != invert
+        })
+    })
+}
+
+/// EDT of a 3D array, indexed by `[[z, y, x]]`.
+///
+/// The outside of the volume is regarded as obstacles like [`crate::edt`].
+/// There is no Fast Marching variant for 3D.
+pub fn edt_array3<T: BoolLike>(map: ArrayView3<T>, invert: bool) -> Array3<f64> {
+    edt_sq_array3(map, invert).mapv_into(f64::sqrt)
+}
+
+/// Squared EDT of a 3D array, indexed by `[[z, y, x]]`.
+///
+/// It applies the 2D transform to each slice, followed by the scan along z axis in the same
+/// manner as the vertical pass of Saito's algorithm.
+pub fn edt_sq_array3<T: BoolLike>(map: ArrayView3<T>, invert: bool) -> Array3<f64> {
+    let (depth, height, width) = map.dim();
+    let mut slices = Array3::zeros((depth, height, width));
+    for (z, slice) in map.axis_iter(Axis(0)).enumerate() {
+        slices
+            .index_axis_mut(Axis(0), z)
+            .assign(&edt_sq_array(slice, invert));
+    }
+
+    let mut ret = Array3::zeros((depth, height, width));
+    for y in 0..height {
+        for x in 0..width {
+            for z in 0..depth {
+                ret[[z, y, x]] = (0..depth)
+                    .map(|z2| (z2 as f64 - z as f64).powf(2.) + slices[[z2, y, x]])
+                    .fold(f64::INFINITY, f64::min)
+                    .min(((z + 1) as f64).powf(2.))
+                    .min(((depth - z) as f64).powf(2.));
+            }
+        }
+    }
+    ret
+}
+
+/// Compute a row-major result of a view, or of its transpose if the view is column-major, so
+/// that the input is scanned in memory order and the output has the same memory order.
+fn with_layout<T>(map: ArrayView2<T>, compute: impl Fn(ArrayView2<T>) -> Vec<f64>) -> Array2<f64> {
+    if !map.is_standard_layout() && map.t().is_standard_layout() {
+        let transposed = map.t();
+        Array2::from_shape_vec(transposed.dim(), compute(transposed))
+            .unwrap()
+            .reversed_axes()
+    } else {
+        Array2::from_shape_vec(map.dim(), compute(map)).unwrap()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{edt, test_util::*};
+    use ndarray::{s, ShapeBuilder};
+
+    #[test]
+    fn test_edt_array() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        let expected = edt(&map, shape, false);
+
+        let array = Array2::from_shape_vec((shape.1, shape.0), map.clone()).unwrap();
+        assert_eq!(edt_array(array.view(), false).into_raw_vec(), expected);
+
+        // Column-major input gives column-major output with the same values.
+        let mut fortran = Array2::from_elem((shape.1, shape.0).f(), false);
+        fortran.assign(&array);
+        let res = edt_array(fortran.view(), false);
+        assert!(res.t().is_standard_layout());
+        assert_eq!(res, Array2::from_shape_vec(res.dim(), expected).unwrap());
+    }
+
+    #[test]
+    fn test_strided() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        // Every other pixel of a wider array
+        let wide = Array2::from_shape_fn((shape.1, shape.0 * 2), |(y, x)| {
+            x % 2 == 0 && map[x / 2 + y * shape.0]
+        });
+        let res = edt_fmm_array(wide.slice(s![.., ..;2]), false);
+        assert_eq!(res.into_raw_vec(), crate::edt_fmm(&map, shape, false));
+    }
+
+    #[test]
+    fn test_edt_array3() {
+        let dim = (4, 5, 6);
+        let map = Array3::from_shape_fn(dim, |(z, y, x)| (z, y, x) != (1, 2, 2));
+        let res = edt_sq_array3(map.view(), false);
+        for ((z, y, x), d) in res.indexed_iter() {
+            let obstacle =
+                (z as f64 - 1.).powf(2.) + (y as f64 - 2.).powf(2.) + (x as f64 - 2.).powf(2.);
+            let border = [z + 1, dim.0 - z, y + 1, dim.1 - y, x + 1, dim.2 - x]
+                .iter()
+                .map(|d| (*d as f64).powf(2.))
+                .fold(obstacle, f64::min);
+            assert_eq!(*d, border);
+        }
+    }
+}
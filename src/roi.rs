@@ -0,0 +1,224 @@
+use super::{
+    fast_marcher::{FastMarcher, Grid},
+    BoolLike, GridPos,
+};
+
+/// A rectangular region of interest in an image.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Roi {
+    /// The position of the top-left pixel of the region.
+    pub origin: GridPos,
+    /// The `(width, height)` of the region.
+    pub shape: (usize, usize),
+}
+
+impl Roi {
+    /// Create a region from the position of its top-left pixel and its `(width, height)`.
+    pub fn new(origin: GridPos, shape: (usize, usize)) -> Self {
+        Self { origin, shape }
+    }
+
+    fn check(&self, shape: (usize, usize)) {
+        assert!(
+            self.origin.0 + self.shape.0 <= shape.0 && self.origin.1 + self.shape.1 <= shape.1,
+            "ROI {:?} is out of the image of shape {:?}",
+            self,
+            shape
+        );
+    }
+
+    fn contains(&self, pos: GridPos) -> bool {
+        (self.origin.0..self.origin.0 + self.shape.0).contains(&pos.0)
+            && (self.origin.1..self.origin.1 + self.shape.1).contains(&pos.1)
+    }
+}
+
+/// EDT of the pixels in a region of interest.
+///
+/// The returned vec has the size of `roi.shape` in row-major order, and each pixel has the same
+/// value as [`crate::edt`] of the whole image. Obstacles outside the region are taken into
+/// account, but only the pixels in the region are computed.
+///
+/// Panics if `roi` does not fit in `shape`.
+pub fn edt_roi<T: BoolLike>(map: &[T], shape: (usize, usize), invert: bool, roi: Roi) -> Vec<f64> {
+    let mut ret = edt_sq_roi(map, shape, invert, roi);
+    for pixel in &mut ret {
+        *pixel = pixel.sqrt();
+    }
+    ret
+}
+
+/// Squared EDT of the pixels in a region of interest.
+///
+/// The interface is equivalent to [`edt_roi`], but it returns squared EDT.
+pub fn edt_sq_roi<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    roi: Roi,
+) -> Vec<f64> {
+    roi.check(shape);
+    let is_fg = |(x, y): GridPos| map[x + y * shape.0].as_bool() != invert;
+
+    // Horizontal pass only for the columns in the region, but for all rows.
+    let horz_edt: Vec<f64> = (0..shape.1)
+        .flat_map(|y| horizontal_edt_roi(shape, y, roi, is_fg))
+        .collect();
+
+    // Vertical pass that scans rows from the nearest, until they cannot be closer.
+    let vertical_scan = |x: usize, y: usize| {
+        let horz = |y2: usize| horz_edt[x - roi.origin.0 + y2 * roi.shape.0];
+        let mut min_edt = horz(y)
+            .powf(2.)
+            .min(((y + 1) as f64).powf(2.))
+            .min(((shape.1 - y) as f64).powf(2.));
+        for dy in 1..shape.1 {
+            let dy_sq = (dy as f64).powf(2.);
+            if min_edt <= dy_sq {
+                break;
+            }
+            for y2 in [y.checked_sub(dy), Some(y + dy).filter(|y2| *y2 < shape.1)]
+                .iter()
+                .flatten()
+            {
+                min_edt = min_edt.min(dy_sq + horz(*y2).powf(2.));
+            }
+        }
+        min_edt
+    };
+
+    (roi.origin.1..roi.origin.1 + roi.shape.1)
+        .flat_map(|y| (roi.origin.0..roi.origin.0 + roi.shape.0).map(move |x| (x, y)))
+        .map(|(x, y)| vertical_scan(x, y))
+        .collect()
+}
+
+/// Horizontal distances of the pixels in row `y` in the columns of `roi`, with the outside of
+/// the image regarded as an obstacle.
+fn horizontal_edt_roi(
+    shape: (usize, usize),
+    y: usize,
+    roi: Roi,
+    is_fg: impl Fn(GridPos) -> bool,
+) -> Vec<f64> {
+    let (left, right) = (roi.origin.0, roi.origin.0 + roi.shape.0);
+    let mut ret = vec![0.; roi.shape.0];
+
+    // Look for the nearest obstacle on the left of the region, or the border.
+    let mut last = (0..left)
+        .rev()
+        .find(|x| !is_fg((*x, y)))
+        .map(|x| (left - x - 1) as f64)
+        .unwrap_or(left as f64);
+    for x in left..right {
+        last = if is_fg((x, y)) { last + 1. } else { 0. };
+        ret[x - left] = last;
+    }
+
+    let mut last = (right..shape.0)
+        .find(|x| !is_fg((*x, y)))
+        .map(|x| (x - right) as f64)
+        .unwrap_or((shape.0 - right) as f64);
+    for x in (left..right).rev() {
+        last = if is_fg((x, y)) { last + 1. } else { 0. };
+        ret[x - left] = ret[x - left].min(last);
+    }
+
+    ret
+}
+
+/// EDT using Fast Marching method of the pixels in a region of interest.
+///
+/// The marcher stops as soon as all the pixels in the region are accepted, so it is faster the
+/// closer the region is to obstacles. Note that it still needs working buffers of the size of
+/// the whole image. See [`edt_roi`] for the arguments.
+pub fn edt_fmm_roi<T: BoolLike>(
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+    roi: Roi,
+) -> Vec<f64> {
+    roi.check(shape);
+    let mut grid = Grid::from_fn(shape, |(x, y)| map[x + y * shape.0].as_bool() != invert);
+    let mut fast_marcher = FastMarcher::new_from_map(&grid, shape);
+
+    let mut remaining = (roi.origin.1..roi.origin.1 + roi.shape.1)
+        .flat_map(|y| (roi.origin.0..roi.origin.0 + roi.shape.0).map(move |x| (x, y)))
+        .filter(|pos| grid[*pos] != 0.)
+        .count();
+    fast_marcher.evolve_until(&mut grid, |pos| {
+        // Each pixel is accepted only once, and its distance does not change afterwards.
+        if roi.contains(pos) {
+            remaining -= 1;
+        }
+        remaining == 0
+    });
+
+    (roi.origin.1..roi.origin.1 + roi.shape.1)
+        .flat_map(|y| {
+            let row = roi.origin.0 + y * shape.0;
+            grid.storage[row..row + roi.shape.0].to_vec()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{edt_fmm, edt_sq, test_util::*};
+
+    fn crop(full: &[f64], shape: (usize, usize), roi: Roi) -> Vec<f64> {
+        (roi.origin.1..roi.origin.1 + roi.shape.1)
+            .flat_map(|y| {
+                let row = roi.origin.0 + y * shape.0;
+                full[row..row + roi.shape.0].to_vec()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_edt_roi() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        let roi = Roi::new((2, 1), (5, 3));
+        assert_eq!(
+            edt_sq_roi(&map, shape, false, roi),
+            crop(&edt_sq(&map, shape, false), shape, roi)
+        );
+
+        let shape = (30, 20);
+        let map = sparse_map(shape);
+        let full = edt_sq(&map, shape, false);
+        for roi in [
+            Roi::new((0, 0), shape),
+            Roi::new((10, 8), (6, 4)),
+            Roi::new((29, 0), (1, 20)),
+            Roi::new((0, 19), (30, 1)),
+        ] {
+            assert_eq!(edt_sq_roi(&map, shape, false, roi), crop(&full, shape, roi));
+        }
+    }
+
+    #[test]
+    fn test_edt_fmm_roi() {
+        let shape = (30, 20);
+        let map = sparse_map(shape);
+        let full = edt_fmm(&map, shape, false);
+        for roi in [
+            Roi::new((0, 0), shape),
+            Roi::new((10, 8), (6, 4)),
+            Roi::new((2, 3), (3, 3)),
+        ] {
+            assert_eq!(
+                edt_fmm_roi(&map, shape, false, roi),
+                crop(&full, shape, roi)
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_image() {
+        edt_roi(&[true; 9], (3, 3), false, Roi::new((1, 1), (3, 1)));
+    }
+}
@@ -0,0 +1,106 @@
+use super::{
+    exact_edt::{horizontal_edt, vertical_edt_sq},
+    BoolLike,
+};
+
+/// A builder of EDT that accepts an image row by row.
+///
+/// The horizontal pass is applied to each row as it is pushed, so you don't need to keep
+/// the whole input image, e.g. while receiving it from a scanner. The vertical pass is deferred
+/// until [`StreamingEdt::finish`], because it needs all rows.
+///
+/// ```
+/// use edt::{edt, StreamingEdt};
+///
+/// let map = [0, 1, 1, 1, 0, 1, 1, 1, 1];
+/// let mut builder = StreamingEdt::new(3, false);
+/// for row in map.chunks(3) {
+///     builder.push_row(row);
+/// }
+/// assert_eq!(builder.finish(), edt(&map, (3, 3), false));
+/// ```
+pub struct StreamingEdt {
+    width: usize,
+    invert: bool,
+    height: usize,
+    horz_edt: Vec<f64>,
+}
+
+impl StreamingEdt {
+    /// Create a builder for an image of the given width.
+    ///
+    /// It assumes zero pixels are obstacles. If you want to invert the logic,
+    /// put `true` to the second argument.
+    pub fn new(width: usize, invert: bool) -> Self {
+        Self {
+            width,
+            invert,
+            height: 0,
+            horz_edt: vec![],
+        }
+    }
+
+    /// Append a row at the bottom of the image.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of `row` is not the width given to [`StreamingEdt::new`].
+    pub fn push_row<T: BoolLike>(&mut self, row: &[T]) {
+        assert_eq!(row.len(), self.width, "row length should match the width");
+        let invert = self.invert;
+        self.horz_edt.extend(horizontal_edt(
+            (self.width, 1),
+            |(x, _)| row[x].as_bool() != invert,
+            true,
+        ));
+        self.height += 1;
+    }
+
+    /// The number of rows pushed so far.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// The shape of the image pushed so far.
+    pub fn shape(&self) -> (usize, usize) {
+        (self.width, self.height())
+    }
+
+    /// Finish the vertical pass and return the EDT, equivalent to [`crate::edt`] of all the rows.
+    pub fn finish(self) -> Vec<f64> {
+        let mut ret = self.finish_sq();
+        for pixel in &mut ret {
+            *pixel = pixel.sqrt();
+        }
+        ret
+    }
+
+    /// Finish the vertical pass and return the squared EDT, equivalent to [`crate::edt_sq`].
+    pub fn finish_sq(self) -> Vec<f64> {
+        vertical_edt_sq(self.shape(), &self.horz_edt, true, f64::INFINITY)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{edt_sq, test_util::*};
+
+    #[test]
+    fn test_streaming() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        let mut builder = StreamingEdt::new(shape.0, false);
+        for row in map.chunks(shape.0) {
+            builder.push_row(row);
+        }
+        assert_eq!(builder.shape(), shape);
+        assert_eq!(builder.finish_sq(), edt_sq(&map, shape, false));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_wrong_width() {
+        StreamingEdt::new(3, false).push_row(&[true; 4]);
+    }
+}
@@ -0,0 +1,129 @@
+use super::{exact_edt::edt_sq_by, fast_marcher::edt_fmm_by, BoolLike, GridPos};
+
+/// A read-only 2D view into a buffer with arbitrary row and pixel strides.
+///
+/// The pixel at `(x, y)` is located at `offset + x * pixel_stride + y * row_stride` in the
+/// underlying slice. It can describe row padding (pitch) as well as a single channel of an
+/// interleaved image, so you can compute EDT of, e.g., the alpha channel of an RGBA buffer
+/// without copying it.
+pub struct StridedView<'a, T> {
+    data: &'a [T],
+    shape: (usize, usize),
+    row_stride: usize,
+    pixel_stride: usize,
+    offset: usize,
+}
+
+impl<'a, T> Clone for StridedView<'a, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T> Copy for StridedView<'a, T> {}
+
+impl<'a, T> StridedView<'a, T> {
+    /// Create a view with explicit strides, counted in elements of `T`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the last pixel of the view lies outside of `data`.
+    pub fn new(
+        data: &'a [T],
+        shape: (usize, usize),
+        row_stride: usize,
+        pixel_stride: usize,
+        offset: usize,
+    ) -> Self {
+        if shape.0 != 0 && shape.1 != 0 {
+            let last = offset + (shape.0 - 1) * pixel_stride + (shape.1 - 1) * row_stride;
+            assert!(
+                last < data.len(),
+                "StridedView out of bounds: last index {} for a slice of length {}",
+                last,
+                data.len()
+            );
+        }
+        Self {
+            data,
+            shape,
+            row_stride,
+            pixel_stride,
+            offset,
+        }
+    }
+
+    /// Create a view of a densely packed row-major image, which is what [`crate::edt`] expects.
+    pub fn dense(data: &'a [T], shape: (usize, usize)) -> Self {
+        Self::new(data, shape, shape.0, 1, 0)
+    }
+
+    /// Create a view of a single channel of an interleaved image without row padding,
+    /// e.g. `StridedView::channel(&rgba, shape, 4, 3)` for the alpha channel of an RGBA image.
+    pub fn channel(data: &'a [T], shape: (usize, usize), channels: usize, channel: usize) -> Self {
+        Self::new(data, shape, shape.0 * channels, channels, channel)
+    }
+
+    /// The dimensions of the view in pixels.
+    pub fn shape(&self) -> (usize, usize) {
+        self.shape
+    }
+
+    /// Returns a reference to the pixel at `pos`.
+    pub fn get(&self, pos: GridPos) -> &'a T {
+        &self.data[self.offset + pos.0 * self.pixel_stride + pos.1 * self.row_stride]
+    }
+}
+
+/// EDT of a strided view.
+///
+/// The returned vec is densely packed in row-major order. See [`crate::edt`] for details.
+pub fn edt_strided<T: BoolLike>(view: StridedView<T>, invert: bool) -> Vec<f64> {
+    let mut ret = edt_sq_strided(view, invert);
+    for pixel in &mut ret {
+        *pixel = pixel.sqrt();
+    }
+    ret
+}
+
+/// Squared EDT of a strided view.
+///
+/// The interface is equivalent to [`edt_strided`], but it returns squared EDT.
+pub fn edt_sq_strided<T: BoolLike>(view: StridedView<T>, invert: bool) -> Vec<f64> {
+    edt_sq_by(view.shape, |pos| view.get(pos).as_bool() != invert, true)
+}
+
+/// EDT using Fast Marching method of a strided view.
+///
+/// The returned vec is densely packed in row-major order. See [`crate::edt_fmm`] for details.
+pub fn edt_fmm_strided<T: BoolLike>(view: StridedView<T>, invert: bool) -> Vec<f64> {
+    edt_fmm_by(view.shape, |pos| view.get(pos).as_bool() != invert)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{edt, edt_fmm, test_util::*};
+
+    #[test]
+    fn test_alpha_channel() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        // RGBA pixels with 2 pixels of padding at the end of each row
+        let row_stride = (shape.0 + 2) * 4;
+        let mut rgba = vec![255u8; row_stride * shape.1];
+        for (i, b) in map.iter().enumerate() {
+            rgba[(i % shape.0) * 4 + (i / shape.0) * row_stride + 3] = *b as u8;
+        }
+        let view = StridedView::new(&rgba, shape, row_stride, 4, 3);
+        assert_eq!(edt_strided(view, false), edt(&map, shape, false));
+        assert_eq!(edt_fmm_strided(view, false), edt_fmm(&map, shape, false));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_out_of_bounds() {
+        let data = [0u8; 16];
+        StridedView::new(&data, (4, 4), 4, 1, 1);
+    }
+}
@@ -15,7 +15,15 @@ pub(crate) fn test_map() -> Vec<bool> {
     map
 }
 
-pub(crate) fn reshape(v: &Vec<f64>, shape: (usize, usize)) -> Vec<Vec<f64>> {
+/// A map with only a few obstacles, far from each other.
+pub(crate) fn sparse_map(shape: (usize, usize)) -> Vec<bool> {
+    let mut map = vec![true; shape.0 * shape.1];
+    map[5 + 7 * shape.0] = false;
+    map[22 + 12 * shape.0] = false;
+    map
+}
+
+pub(crate) fn reshape(v: &[f64], shape: (usize, usize)) -> Vec<Vec<f64>> {
     let mut ret = vec![];
 
     for y in 0..shape.1 {
@@ -34,7 +42,7 @@ pub(crate) fn print_2d(v: &[Vec<f64>]) {
                 print!("{:.1}", cell);
             }
         }
-        print!("\n");
+        println!();
     }
 }
 
@@ -49,7 +57,7 @@ pub(crate) fn parse_edt_str(s: &[&str]) -> Vec<f64> {
                 s.chars()
                     .map(|c| {
                         if c != 'f' {
-                            (c as u8 - '0' as u8) as f64
+                            (c as u8 - b'0') as f64
                         } else {
                             15.
                         }
@@ -0,0 +1,209 @@
+use super::exact_edt::horizontal_edt;
+
+/// A source of image rows for [`edt_tiled`].
+///
+/// It is implemented for closures of the same signature as [`BandReader::read_band`].
+pub trait BandReader {
+    /// Fill `band` with the pixels of consecutive rows starting from row `y`.
+    ///
+    /// `band.len()` is a multiple of the image width. Like [`crate::edt`], `false` pixels are
+    /// obstacles unless `invert` is given to [`edt_tiled`].
+    fn read_band(&mut self, y: usize, band: &mut [bool]);
+}
+
+impl<F: FnMut(usize, &mut [bool])> BandReader for F {
+    fn read_band(&mut self, y: usize, band: &mut [bool]) {
+        self(y, band)
+    }
+}
+
+/// A destination of EDT rows for [`edt_tiled`].
+///
+/// It is implemented for closures of the same signature as [`BandWriter::write_band`].
+pub trait BandWriter {
+    /// Store the distances of consecutive rows starting from row `y`.
+    fn write_band(&mut self, y: usize, band: &[f64]);
+}
+
+impl<F: FnMut(usize, &[f64])> BandWriter for F {
+    fn write_band(&mut self, y: usize, band: &[f64]) {
+        self(y, band)
+    }
+}
+
+/// EDT of an image too large to fit in memory, processed in bands of rows.
+///
+/// The result is the same as [`crate::edt`], but the image is read through `reader` and the
+/// distances are written through `writer`, `band_height` rows at a time in top-to-bottom order.
+/// Only two bands are kept in memory at once, so the memory usage does not depend on the image
+/// height.
+///
+/// For each output band, input bands are read from the nearest one and the horizontal pass is
+/// applied to them on the fly, until the remaining bands are too far to give a shorter distance.
+/// It means the same band may be read several times, especially if obstacles are sparse.
+/// Returns the number of bands read, which tells how many times the input was scanned.
+///
+/// Panics if `band_height` is zero.
+pub fn edt_tiled(
+    reader: impl BandReader,
+    writer: impl BandWriter,
+    shape: (usize, usize),
+    band_height: usize,
+    invert: bool,
+) -> usize {
+    let mut writer = writer;
+    edt_sq_tiled(
+        reader,
+        |y, band: &[f64]| {
+            let band: Vec<_> = band.iter().map(|p| p.sqrt()).collect();
+            writer.write_band(y, &band);
+        },
+        shape,
+        band_height,
+        invert,
+    )
+}
+
+/// Squared EDT of an image processed in bands of rows.
+///
+/// The interface is equivalent to [`edt_tiled`], but it writes squared EDT.
+pub fn edt_sq_tiled(
+    mut reader: impl BandReader,
+    mut writer: impl BandWriter,
+    shape: (usize, usize),
+    band_height: usize,
+    invert: bool,
+) -> usize {
+    assert!(0 < band_height, "band_height should be positive");
+    let (width, height) = shape;
+    let num_bands = height.div_ceil(band_height);
+    let band_rows = |band: usize| band * band_height..((band + 1) * band_height).min(height);
+
+    let mut input = vec![false; width * band_height];
+    let mut read_count = 0;
+    let mut read_horz_edt = |band: usize| {
+        let rows = band_rows(band);
+        let input = &mut input[..width * rows.len()];
+        reader.read_band(rows.start, input);
+        read_count += 1;
+        horizontal_edt(
+            (width, rows.len()),
+            |(x, y)| input[x + y * width] != invert,
+            true,
+        )
+    };
+
+    for band in 0..num_bands {
+        let rows = band_rows(band);
+        let mut output: Vec<f64> = rows
+            .clone()
+            .flat_map(|y| {
+                let border = ((y + 1) as f64).min((height - y) as f64).powf(2.);
+                (0..width).map(move |_| border)
+            })
+            .collect();
+
+        // Visit input bands in the order of the distance to the output band.
+        let input_bands = (0..num_bands).flat_map(|d| {
+            let above = band.checked_sub(d).filter(|_| d != 0);
+            let below = Some(band + d).filter(|b| *b < num_bands);
+            above.into_iter().chain(below)
+        });
+        for input_band in input_bands {
+            let input_rows = band_rows(input_band);
+            let gap = if input_rows.end <= rows.start {
+                rows.start - input_rows.end + 1
+            } else {
+                input_rows.start.saturating_sub(rows.end - 1)
+            };
+            let max_edt = output.iter().copied().fold(0., f64::max);
+            if max_edt <= (gap as f64).powf(2.) {
+                break;
+            }
+
+            let horz_edt = read_horz_edt(input_band);
+            for (y, output_row) in rows.clone().zip(output.chunks_mut(width)) {
+                for (y2, horz_row) in input_rows.clone().zip(horz_edt.chunks(width)) {
+                    let dy_sq = (y2 as f64 - y as f64).powf(2.);
+                    for (pixel, horz) in output_row.iter_mut().zip(horz_row) {
+                        *pixel = pixel.min(dy_sq + horz.powf(2.));
+                    }
+                }
+            }
+        }
+
+        writer.write_band(rows.start, &output);
+    }
+
+    read_count
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{edt, edt_sq, test_util::*};
+
+    fn run_tiled(map: &[bool], shape: (usize, usize), band_height: usize) -> (Vec<f64>, usize) {
+        let mut ret = vec![f64::NAN; map.len()];
+        let reads = edt_sq_tiled(
+            |y, band: &mut [bool]| {
+                band.copy_from_slice(&map[y * shape.0..y * shape.0 + band.len()]);
+            },
+            |y, band: &[f64]| {
+                ret[y * shape.0..y * shape.0 + band.len()].copy_from_slice(band);
+            },
+            shape,
+            band_height,
+            false,
+        );
+        (ret, reads)
+    }
+
+    #[test]
+    fn test_edt_tiled() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        for band_height in 1..=6 {
+            assert_eq!(
+                run_tiled(&map, shape, band_height).0,
+                edt_sq(&map, shape, false)
+            );
+        }
+
+        let shape = (16, 40);
+        let mut map = vec![true; shape.0 * shape.1];
+        map[3 + 7 * shape.0] = false;
+        map[12 + 30 * shape.0] = false;
+        for band_height in [1, 4, 7, 40] {
+            assert_eq!(
+                run_tiled(&map, shape, band_height).0,
+                edt_sq(&map, shape, false)
+            );
+        }
+    }
+
+    #[test]
+    fn test_early_termination() {
+        // Every row has obstacles, so each band only depends on itself.
+        let shape = (8, 64);
+        let map: Vec<_> = (0..shape.0 * shape.1).map(|i| i % 3 != 0).collect();
+        let (edt, reads) = run_tiled(&map, shape, 4);
+        assert_eq!(edt, edt_sq(&map, shape, false));
+        assert_eq!(reads, 16);
+    }
+
+    #[test]
+    fn test_sqrt() {
+        let map = test_map();
+        let shape = (map.len() / 5, 5);
+        let mut ret = vec![];
+        edt_tiled(
+            |y, band: &mut [bool]| band.copy_from_slice(&map[y * shape.0..][..band.len()]),
+            |_, band: &[f64]| ret.extend_from_slice(band),
+            shape,
+            2,
+            false,
+        );
+        assert_eq!(ret, edt(&map, shape, false));
+    }
+}
@@ -0,0 +1,182 @@
+use super::{exact_edt::feature_transform_by, fast_marcher::voronoi_fmm_by, BoolLike, GridPos};
+
+/// The result of Voronoi tessellation, see [`voronoi_labels`].
+pub struct Voronoi<L> {
+    /// The label of the nearest seed for each pixel, or `None` if no seed is reachable.
+    pub labels: Vec<Option<L>>,
+    /// The distance to the nearest seed for each pixel, or infinity if no seed is reachable.
+    pub distance: Vec<f64>,
+}
+
+/// Discrete Euclidean Voronoi tessellation from seed points.
+///
+/// Each pixel gets the label of its nearest seed and the distance to it, computed by the exact
+/// feature transform. If several seeds share a pixel, the last one wins.
+///
+/// Panics if a seed is outside of `shape`.
+pub fn voronoi_labels<L: Copy>(seeds: &[(GridPos, L)], shape: (usize, usize)) -> Voronoi<L> {
+    let seed_map = rasterize_seeds(seeds, shape);
+    let ft = feature_transform_by(shape, |(x, y)| seed_map[x + y * shape.0].is_none());
+
+    let (labels, distance) = ft
+        .iter()
+        .enumerate()
+        .map(|(i, feature)| match feature {
+            Some((fx, fy)) => {
+                let (x, y) = (i % shape.0, i / shape.0);
+                let dist =
+                    ((*fx as f64 - x as f64).powf(2.) + (*fy as f64 - y as f64).powf(2.)).sqrt();
+                (seed_map[fx + fy * shape.0].map(|seed| seeds[seed].1), dist)
+            }
+            None => (None, f64::INFINITY),
+        })
+        .unzip();
+
+    Voronoi { labels, distance }
+}
+
+/// Discrete Euclidean Voronoi tessellation from a labeled seed image.
+///
+/// Nonzero pixels (in the sense of [`BoolLike`]) are seeds labeled with their own value, and
+/// zero pixels are filled with the label of the nearest seed.
+pub fn voronoi_labels_image<L: BoolLike + Copy>(seeds: &[L], shape: (usize, usize)) -> Voronoi<L> {
+    voronoi_labels(&image_seeds(seeds, shape), shape)
+}
+
+/// Geodesic Voronoi tessellation from seed points using Fast Marching method.
+///
+/// Unlike [`voronoi_labels`], distances are measured along paths that avoid obstacles in `map`,
+/// so a seed behind a wall does not claim pixels on the other side. It assumes zero pixels in
+/// `map` are obstacles, and `invert` flips the logic like [`crate::edt_fmm`]. Obstacle pixels
+/// and pixels unreachable from any seed get no label and infinite distance.
+///
+/// Panics if a seed is outside of `shape`.
+pub fn voronoi_fmm<L: Copy, T: BoolLike>(
+    seeds: &[(GridPos, L)],
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+) -> Voronoi<L> {
+    let seed_map = rasterize_seeds(seeds, shape);
+    let (distance, labels) = voronoi_fmm_by(shape, &seed_map, |(x, y)| {
+        map[x + y * shape.0].as_bool() != invert
+    });
+    Voronoi {
+        labels: labels
+            .into_iter()
+            .map(|seed| seed.map(|seed| seeds[seed].1))
+            .collect(),
+        distance,
+    }
+}
+
+/// Geodesic Voronoi tessellation from a labeled seed image using Fast Marching method.
+///
+/// See [`voronoi_labels_image`] and [`voronoi_fmm`] for the meaning of the arguments.
+pub fn voronoi_fmm_image<L: BoolLike + Copy, T: BoolLike>(
+    seeds: &[L],
+    map: &[T],
+    shape: (usize, usize),
+    invert: bool,
+) -> Voronoi<L> {
+    voronoi_fmm(&image_seeds(seeds, shape), map, shape, invert)
+}
+
+fn image_seeds<L: BoolLike + Copy>(seeds: &[L], shape: (usize, usize)) -> Vec<(GridPos, L)> {
+    seeds
+        .iter()
+        .enumerate()
+        .filter(|(_, label)| label.as_bool())
+        .map(|(i, label)| ((i % shape.0, i / shape.0), *label))
+        .collect()
+}
+
+/// Returns the index of the seed at each pixel.
+fn rasterize_seeds<L>(seeds: &[(GridPos, L)], shape: (usize, usize)) -> Vec<Option<usize>> {
+    let mut seed_map = vec![None; shape.0 * shape.1];
+    for (i, ((x, y), _)) in seeds.iter().enumerate() {
+        assert!(
+            *x < shape.0 && *y < shape.1,
+            "Seed ({}, {}) is out of the image of shape {:?}",
+            x,
+            y,
+            shape
+        );
+        seed_map[x + y * shape.0] = Some(i);
+    }
+    seed_map
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_voronoi_labels() {
+        let shape = (6, 4);
+        let seeds = [((0, 0), 'a'), ((5, 3), 'b'), ((4, 0), 'c')];
+        let voronoi = voronoi_labels(&seeds, shape);
+        for (i, (label, dist)) in voronoi.labels.iter().zip(&voronoi.distance).enumerate() {
+            let (x, y) = ((i % shape.0) as f64, (i / shape.0) as f64);
+            let brute = seeds
+                .iter()
+                .map(|((sx, sy), _)| ((*sx as f64 - x).powf(2.) + (*sy as f64 - y).powf(2.)).sqrt())
+                .reduce(f64::min)
+                .unwrap();
+            assert_eq!(*dist, brute);
+            let (seed_pos, _) = seeds.iter().find(|(_, l)| Some(*l) == *label).unwrap();
+            let seed_dist =
+                ((seed_pos.0 as f64 - x).powf(2.) + (seed_pos.1 as f64 - y).powf(2.)).sqrt();
+            assert_eq!(seed_dist, brute);
+        }
+    }
+
+    #[test]
+    fn test_voronoi_labels_image() {
+        let seeds = [0u16, 0, 0, 3, 0, 0, 0, 9];
+        let voronoi = voronoi_labels_image(&seeds, (4, 2));
+        assert_eq!(voronoi.labels, [3, 3, 3, 3, 9, 9, 9, 9].map(Some).to_vec());
+    }
+
+    #[test]
+    fn test_voronoi_fmm() {
+        // A full-height wall with a single opening at the bottom separates the two seeds.
+        let map = [
+            "111101111", //
+            "111101111", //
+            "111101111", //
+            "111101111", //
+            "111101111", //
+            "111101111", //
+            "111111111",
+        ]
+        .iter()
+        .flat_map(|s| s.chars().map(|c| c == '1'))
+        .collect::<Vec<_>>();
+        let shape = (9, 7);
+        let seeds = [((2, 0), 1u8), ((8, 3), 2u8)];
+        let voronoi = voronoi_fmm(&seeds, &map, shape, false);
+        let euclidean = voronoi_labels(&seeds, shape);
+
+        // (5, 0) is 3 pixels from the left seed in a straight line, but the path to it has to
+        // go around the wall, so the right seed is nearer in the geodesic sense.
+        let i = 5;
+        assert_eq!(euclidean.labels[i], Some(1));
+        assert_eq!(euclidean.distance[i], 3.);
+        assert_eq!(voronoi.labels[i], Some(2));
+        assert!(euclidean.distance[i] < voronoi.distance[i]);
+
+        assert_eq!(voronoi.labels[4 + 2 * shape.0], None);
+        assert_eq!(voronoi.distance[4 + 2 * shape.0], f64::INFINITY);
+        assert_eq!(voronoi.labels[0], Some(1));
+        assert_eq!(voronoi.distance[2], 0.);
+        assert_eq!(voronoi.distance[1], 1.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_seed_out_of_image() {
+        // (4, 0) would wrap around to (0, 1) without the bounds check.
+        voronoi_labels(&[((4, 0), 'a')], (4, 2));
+    }
+}